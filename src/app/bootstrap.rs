@@ -1,18 +1,20 @@
 use std::sync::Arc;
 
 use crate::app::{controller::AppController, market_registry::MarketRegistry};
-use crate::config::registry::ConfigRegistry;
+use crate::config::{registry::ConfigRegistry, Settings, Workspace};
 use crate::error::{Context, Result};
 
 /// Entry point used by `main` to bootstrap the controller stack.
-pub async fn run() -> Result<()> {
-    let root = std::env::current_dir().context("Failed to determine project root")?;
-    let config_registry = Arc::new(ConfigRegistry::new(root)?);
+pub async fn run(workspace: Workspace, settings: Settings) -> Result<()> {
+    let config_registry = Arc::new(ConfigRegistry::new(workspace)?);
     config_registry
         .start_watching()
         .context("Failed to start config watcher")?;
 
-    let market_registry = Arc::new(MarketRegistry::new(Arc::clone(&config_registry)));
+    let market_registry = Arc::new(MarketRegistry::new(
+        Arc::clone(&config_registry),
+        Arc::new(settings),
+    ));
     let controller = AppController::new(Arc::clone(&market_registry))?;
     controller.run().await
 }
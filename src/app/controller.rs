@@ -1,7 +1,10 @@
 use std::path::Path;
 use std::sync::Arc;
 
-use crate::app::{market_registry::MarketRegistry, state::RegionState};
+use crate::app::{
+    market_registry::MarketRegistry,
+    state::{RegionState, ResumePlan},
+};
 use crate::config::{RegionConfig, RegionDescriptor};
 use crate::error::{AppError, Result};
 use crate::ui::{
@@ -9,6 +12,7 @@ use crate::ui::{
     run_preset_picker, run_results_table, run_thresholds_editor, FilterMenuAction, MenuAction,
 };
 use crate::utils::sanitize_preset_name;
+use log::warn;
 use tokio::sync::watch;
 
 /// Coordinates configuration, region state, and TUI flows.
@@ -49,8 +53,11 @@ impl AppController {
             self.load_previous_snapshot(&mut region_state);
 
             if region_state.database().data.is_empty() {
+                // A recent interrupted fetch picks up where it stopped; stale ones start over.
+                let resume = self.resume_plan(&region_state);
                 self.fetch_and_persist(
                     &mut region_state,
+                    resume,
                     "Fetch cancelled.",
                     "Failed to fetch data",
                 )
@@ -76,20 +83,43 @@ impl AppController {
     ) -> Result<ControllerOutcome> {
         loop {
             let allow_region_switch = self.markets.available_regions().len() > 1;
+            let resume_hint = self.resume_plan(region_state).map(|plan| {
+                format!(
+                    "Fetch the {} symbols left from {}",
+                    plan.remaining.len(),
+                    plan.started_at
+                )
+            });
             match run_main_menu(
                 region_state.loaded_file(),
                 allow_region_switch,
                 &region_state.config().code,
                 &region_state.config().name,
+                resume_hint.as_deref(),
             )? {
                 MenuAction::Update => {
                     self.fetch_and_persist(
                         region_state,
+                        None,
                         "Update cancelled.",
                         "Failed to refresh data",
                     )
                     .await;
                 }
+                MenuAction::Resume => {
+                    let resume = self.resume_plan(region_state);
+                    if resume.is_none() {
+                        println!("Nothing left to resume.");
+                        continue;
+                    }
+                    self.fetch_and_persist(
+                        region_state,
+                        resume,
+                        "Resume cancelled.",
+                        "Failed to resume fetch",
+                    )
+                    .await;
+                }
                 MenuAction::Filter => {
                     let codes = region_state
                         .database()
@@ -157,26 +187,23 @@ impl AppController {
                 }
                 FilterMenuAction::Load => {
                     let (_, filters_dir) = region_state.directories();
-                    match run_preset_picker(&filters_dir)? {
-                        Some(path) => {
-                            match region_state
-                                .records()
-                                .load_threshold_preset(Path::new(&path))
-                            {
-                                Ok(loaded) => {
-                                    region_state.set_thresholds(loaded);
-                                    let name = Path::new(&path)
-                                        .file_name()
-                                        .and_then(|s| s.to_str())
-                                        .unwrap_or(&path);
-                                    println!("Applied filters from {}", name);
-                                }
-                                Err(err) => {
-                                    eprintln!("Failed to load filters: {}", err);
-                                }
+                    if let Some(path) = run_preset_picker(&filters_dir)? {
+                        match region_state
+                            .records()
+                            .load_threshold_preset(Path::new(&path))
+                        {
+                            Ok(loaded) => {
+                                region_state.set_thresholds(loaded);
+                                let name = Path::new(&path)
+                                    .file_name()
+                                    .and_then(|s| s.to_str())
+                                    .unwrap_or(&path);
+                                println!("Applied filters from {}", name);
+                            }
+                            Err(err) => {
+                                eprintln!("Failed to load filters: {}", err);
                             }
                         }
-                        None => {}
                     }
                 }
                 FilterMenuAction::Back => break,
@@ -204,28 +231,68 @@ impl AppController {
         Ok(())
     }
 
+    /// Fetch every symbol, or only those `resume` still needs, then persist the snapshot, its
+    /// report, and the checkpoint state.
     async fn fetch_and_persist(
         &self,
         region_state: &mut RegionState,
+        resume: Option<ResumePlan>,
         cancel_message: &str,
         error_message: &str,
     ) {
-        match run_fetch_progress(
-            region_state.stock_codes(),
-            region_state.config().clone(),
-            region_state.stock_names().clone(),
-        )
-        .await
-        {
-            Ok(data) => match region_state.apply_snapshot(data) {
-                Ok(saved_path) => println!("Saved: {}", saved_path.display()),
-                Err(err) => eprintln!("Failed to persist snapshot: {}", err),
-            },
+        let fetcher = match &resume {
+            Some(plan) => region_state.resume_fetcher(plan),
+            None => region_state.fresh_fetcher(),
+        };
+        let fetcher = match fetcher {
+            Ok(fetcher) => fetcher,
+            Err(err) => {
+                eprintln!("{}: {}", error_message, err);
+                return;
+            }
+        };
+
+        match run_fetch_progress(fetcher).await {
+            Ok(snapshot) => {
+                let saved = match resume {
+                    Some(plan) => {
+                        let mut rows = plan.completed;
+                        rows.extend(snapshot.data);
+                        region_state.merge_snapshot(rows)
+                    }
+                    None => region_state.apply_snapshot(snapshot.data),
+                };
+                let saved_path = match saved {
+                    Ok(saved_path) => saved_path,
+                    Err(err) => {
+                        eprintln!("Failed to persist snapshot: {}", err);
+                        return;
+                    }
+                };
+                println!("Saved: {}", saved_path.display());
+                match region_state
+                    .records()
+                    .save_fetch_report(&saved_path, &snapshot.report)
+                {
+                    Ok(report_path) => println!("Report: {}", report_path.display()),
+                    Err(err) => eprintln!("Failed to persist fetch report: {}", err),
+                }
+                if let Err(err) = region_state.settle_checkpoint(&snapshot.report) {
+                    warn!("{}", err);
+                }
+            }
             Err(AppError::Cancelled) => println!("{}", cancel_message),
             Err(err) => eprintln!("{}: {}", error_message, err),
         }
     }
 
+    fn resume_plan(&self, region_state: &RegionState) -> Option<ResumePlan> {
+        region_state.resume_plan().unwrap_or_else(|err| {
+            warn!("{}", err);
+            None
+        })
+    }
+
     fn load_previous_snapshot(&self, region_state: &mut RegionState) {
         match region_state.records().latest_snapshot() {
             Ok(Some((path, name))) => match region_state.records().load_snapshot(&path) {
@@ -286,6 +353,19 @@ impl AppController {
                 return Ok(Some(summaries[0].code.clone()));
             }
 
+            if let Some(default) = &self.markets.settings().default_region {
+                if let Some(summary) = summaries
+                    .iter()
+                    .find(|summary| summary.code.eq_ignore_ascii_case(default))
+                {
+                    return Ok(Some(summary.code.clone()));
+                }
+                warn!(
+                    "Default region `{}` is not configured; showing picker",
+                    default
+                );
+            }
+
             let options: Vec<(String, String)> = summaries
                 .into_iter()
                 .map(|summary| (summary.code, summary.name))
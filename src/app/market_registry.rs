@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use tokio::sync::watch;
 
-use crate::config::{registry::ConfigRegistry, RegionConfig, RegionDescriptor};
+use crate::config::{registry::ConfigRegistry, RegionConfig, RegionDescriptor, Settings};
 use crate::error::{AppError, Result};
 
 /// Lightweight summary used for market pickers.
@@ -15,11 +15,16 @@ pub struct MarketSummary {
 /// Facade over `ConfigRegistry` that exposes market descriptors to the application/UI layer.
 pub struct MarketRegistry {
     registry: Arc<ConfigRegistry>,
+    settings: Arc<Settings>,
 }
 
 impl MarketRegistry {
-    pub fn new(registry: Arc<ConfigRegistry>) -> Self {
-        Self { registry }
+    pub fn new(registry: Arc<ConfigRegistry>, settings: Arc<Settings>) -> Self {
+        Self { registry, settings }
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
     }
 
     pub fn available_regions(&self) -> Vec<MarketSummary> {
@@ -39,7 +44,7 @@ impl MarketRegistry {
 
     pub fn region_config(&self, code: &str) -> Option<RegionConfig> {
         self.region_descriptor(code)
-            .map(|descriptor| RegionConfig::from(&descriptor))
+            .map(|descriptor| self.settings.apply(RegionConfig::from(&descriptor)))
     }
 
     pub fn subscribe(&self) -> watch::Receiver<Arc<Vec<RegionDescriptor>>> {
@@ -3,14 +3,25 @@ use std::path::{Path, PathBuf};
 
 use crate::config::{RegionConfig, Threshold};
 use crate::error::{AppError, Context, Result};
-use crate::fetch::StockData;
+use crate::fetch::{FetchCheckpoint, FetchReport, SnapshotFetcher, StockData};
 use crate::records::{Records, StockDatabase};
+use crate::utils::resolve_named_file;
+use log::warn;
 
 struct LoadedStockCodes {
     codes: Vec<String>,
     names: HashMap<String, String>,
 }
 
+/// Work left over from an interrupted or partially failed snapshot fetch.
+pub struct ResumePlan {
+    pub started_at: String,
+    /// Rows already fetched, as recorded in the checkpoint.
+    pub completed: Vec<StockData>,
+    /// Symbols that failed or were never reached, in stock list order.
+    pub remaining: Vec<String>,
+}
+
 /// Region-scoped runtime data shared across UI flows and fetch tasks.
 pub struct RegionState {
     config: RegionConfig,
@@ -23,14 +34,22 @@ pub struct RegionState {
 }
 
 impl RegionState {
-    /// Prepare the region by loading codes, ensuring directories, and seeding thresholds.
+    /// Prepare the region by loading codes, ensuring directories, and seeding thresholds from
+    /// the configured default preset when one is set.
     pub async fn new(config: RegionConfig) -> Result<Self> {
         let LoadedStockCodes { codes, names } = prepare_stock_codes(&config).await?;
 
         let records = Records::for_region(&config);
         records.prepare()?;
 
-        let thresholds = records.initial_thresholds(&config);
+        let mut thresholds = records.initial_thresholds(&config);
+        if let Some(name) = &config.default_preset {
+            let path = resolve_named_file(records.presets_dir(), name, "json");
+            match records.load_threshold_preset(&path) {
+                Ok(preset) => thresholds = preset,
+                Err(err) => warn!("Failed to load default preset {}: {}", path.display(), err),
+            }
+        }
 
         Ok(Self {
             config,
@@ -90,6 +109,16 @@ impl RegionState {
     /// Replace the in-memory snapshot and persist it to disk.
     pub fn apply_snapshot(&mut self, data: Vec<StockData>) -> Result<PathBuf> {
         self.database.update(data);
+        self.persist_database()
+    }
+
+    /// Merge fetched rows into the current snapshot and persist the result.
+    pub fn merge_snapshot(&mut self, rows: Vec<StockData>) -> Result<PathBuf> {
+        self.database.merge(rows);
+        self.persist_database()
+    }
+
+    fn persist_database(&mut self) -> Result<PathBuf> {
         let saved_path = self.records.save_snapshot(&self.database)?;
         let name = saved_path
             .file_name()
@@ -100,6 +129,86 @@ impl RegionState {
         Ok(saved_path)
     }
 
+    pub fn fetch_checkpoint(&self) -> FetchCheckpoint {
+        self.records.fetch_checkpoint(&self.config.code)
+    }
+
+    /// Symbols still missing from the checkpoint, or `None` when there is nothing to resume.
+    ///
+    /// A checkpoint older than `fetch.resume_max_age` is discarded: merging its quotes into a new
+    /// snapshot would pass stale prices off as current.
+    pub fn resume_plan(&self) -> Result<Option<ResumePlan>> {
+        let checkpoint = self.fetch_checkpoint();
+        let Some(state) = checkpoint.load()? else {
+            return Ok(None);
+        };
+        let max_age = self.config.fetch.resume_max_age;
+        if state.age().is_none_or(|age| age > max_age) {
+            warn!(
+                "Discarding fetch checkpoint from {}: older than {}s, its quotes are stale",
+                state.started_at,
+                max_age.as_secs()
+            );
+            checkpoint.clear()?;
+            return Ok(None);
+        }
+
+        let done: std::collections::HashSet<&str> = state
+            .rows
+            .iter()
+            .map(|row| row.stock_code.as_str())
+            .collect();
+        let remaining: Vec<String> = self
+            .stock_codes
+            .iter()
+            .filter(|code| !done.contains(code.as_str()))
+            .cloned()
+            .collect();
+        if remaining.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(ResumePlan {
+            started_at: state.started_at,
+            completed: state.rows,
+            remaining,
+        }))
+    }
+
+    /// Fetcher for every symbol, starting a fresh checkpoint.
+    pub fn fresh_fetcher(&self) -> Result<SnapshotFetcher> {
+        let checkpoint = self.fetch_checkpoint();
+        checkpoint.start()?;
+        self.fetcher_for(self.stock_codes.clone(), checkpoint)
+    }
+
+    /// Fetcher for the symbols `plan` still needs, appending to the existing checkpoint.
+    pub fn resume_fetcher(&self, plan: &ResumePlan) -> Result<SnapshotFetcher> {
+        let checkpoint = self.fetch_checkpoint();
+        checkpoint.resume()?;
+        self.fetcher_for(plan.remaining.clone(), checkpoint)
+    }
+
+    fn fetcher_for(
+        &self,
+        codes: Vec<String>,
+        checkpoint: FetchCheckpoint,
+    ) -> Result<SnapshotFetcher> {
+        Ok(
+            SnapshotFetcher::new(codes, self.config.clone(), self.stock_names.clone())?
+                .with_checkpoint(checkpoint),
+        )
+    }
+
+    /// Drop the checkpoint once a fetch reached every symbol; keep it for a later resume
+    /// otherwise.
+    pub fn settle_checkpoint(&self, report: &FetchReport) -> Result<()> {
+        if report.failed_count() == 0 {
+            self.fetch_checkpoint().clear()?;
+        }
+        Ok(())
+    }
+
     pub fn directories(&self) -> (String, String) {
         let snapshots = self.records.snapshots_dir().to_string_lossy().to_string();
         let presets = self.records.presets_dir().to_string_lossy().to_string();
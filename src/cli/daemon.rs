@@ -0,0 +1,212 @@
+use std::time::{Duration, Instant};
+
+use chrono::{Local, Utc};
+use log::{info, warn};
+use tokio::sync::watch;
+
+use crate::app::state::RegionState;
+use crate::config::{Settings, Workspace};
+use crate::error::{AppError, Result};
+use crate::fetch::SnapshotFetcher;
+use crate::records::StockDatabase;
+use crate::utils::Schedule;
+
+use super::open_region;
+
+/// Interval used when neither `--interval` nor `--cron` is given.
+pub const DEFAULT_DAEMON_INTERVAL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone)]
+pub struct DaemonOptions {
+    /// Regions to snapshot each cycle; empty means the settings `default_region`.
+    pub regions: Vec<String>,
+    pub schedule: Schedule,
+    /// Fetch even when a region declares trading sessions and is currently closed.
+    pub ignore_sessions: bool,
+}
+
+enum RegionOutcome {
+    Saved,
+    Skipped,
+    Failed,
+}
+
+/// Snapshot the selected regions on a schedule until SIGINT or SIGTERM arrives.
+pub async fn run_daemon(
+    workspace: &Workspace,
+    settings: &Settings,
+    options: DaemonOptions,
+) -> Result<()> {
+    let mut regions = Vec::new();
+    if options.regions.is_empty() {
+        regions.push(open_region(workspace, settings, None).await?);
+    } else {
+        for code in &options.regions {
+            regions.push(open_region(workspace, settings, Some(code)).await?);
+        }
+    }
+
+    let (shutdown_tx, mut shutdown) = watch::channel(false);
+    tokio::spawn(async move {
+        wait_for_signal().await;
+        let _ = shutdown_tx.send(true);
+    });
+
+    info!(
+        "Daemon started for {}",
+        regions
+            .iter()
+            .map(|region| region.config().code.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let mut cycle = 0usize;
+    let mut last_started = None;
+    loop {
+        // Intervals are measured between cycle starts so slow fetches do not drift the cadence;
+        // an interval schedule also runs once immediately.
+        let base = match (&options.schedule, last_started) {
+            (Schedule::Interval(_), Some(started)) => Some(started),
+            (Schedule::Interval(_), None) => None,
+            (Schedule::Cron(_), _) => Some(Local::now()),
+        };
+        if let Some(base) = base {
+            let next = options.schedule.next_after(base).ok_or_else(|| {
+                AppError::message("The schedule has no upcoming run time; stopping the daemon")
+            })?;
+            info!("Next cycle at {}", next.format("%Y-%m-%d %H:%M:%S"));
+            let wait = (next - Local::now()).to_std().unwrap_or_default();
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = shutdown_requested(&mut shutdown) => break,
+            }
+        }
+
+        cycle += 1;
+        last_started = Some(Local::now());
+        let started = Instant::now();
+        let (mut saved, mut skipped, mut failed) = (0, 0, 0);
+        for region in &regions {
+            let outcome = tokio::select! {
+                outcome = snapshot_region(region, options.ignore_sessions) => outcome,
+                _ = shutdown_requested(&mut shutdown) => {
+                    info!("Shutdown requested during cycle {}; abandoning in-flight fetch", cycle);
+                    return Ok(());
+                }
+            };
+            match outcome {
+                RegionOutcome::Saved => saved += 1,
+                RegionOutcome::Skipped => skipped += 1,
+                RegionOutcome::Failed => failed += 1,
+            }
+        }
+        info!(
+            "Cycle {} finished in {:.1}s: {} saved, {} skipped, {} failed",
+            cycle,
+            started.elapsed().as_secs_f64(),
+            saved,
+            skipped,
+            failed
+        );
+    }
+
+    info!("Daemon stopped after {} cycle(s)", cycle);
+    Ok(())
+}
+
+async fn snapshot_region(region: &RegionState, ignore_sessions: bool) -> RegionOutcome {
+    let config = region.config();
+    if !ignore_sessions {
+        if let Some(calendar) = &config.trading_sessions {
+            if !calendar.is_open(&Utc::now()) {
+                info!("{}: outside trading sessions, skipped", config.code);
+                return RegionOutcome::Skipped;
+            }
+        }
+    }
+
+    let started = Instant::now();
+    let fetcher = match SnapshotFetcher::new(
+        region.stock_codes().to_vec(),
+        config.clone(),
+        region.stock_names().clone(),
+    ) {
+        Ok(fetcher) => fetcher,
+        Err(err) => {
+            warn!("{}: snapshot failed: {}", config.code, err);
+            return RegionOutcome::Failed;
+        }
+    };
+    let total = fetcher.total_stocks;
+    let result = fetcher.fetch_data().await.and_then(|snapshot| {
+        let fetched = snapshot.data.len();
+        let records = region.records();
+        let path = records.save_snapshot(&StockDatabase::new(snapshot.data))?;
+        records.save_fetch_report(&path, &snapshot.report)?;
+        Ok((fetched, path, snapshot.report))
+    });
+
+    match result {
+        Ok((fetched, path, report)) => {
+            info!(
+                "{}: saved {}/{} symbols to {} in {:.1}s",
+                config.code,
+                fetched,
+                total,
+                path.display(),
+                started.elapsed().as_secs_f64()
+            );
+            if report.failed_count() > 0 {
+                warn!(
+                    "{}: {} symbol(s) failed: {}",
+                    config.code,
+                    report.failed_count(),
+                    report.failure_summary()
+                );
+            }
+            RegionOutcome::Saved
+        }
+        Err(err) => {
+            warn!(
+                "{}: snapshot failed after {:.1}s: {}",
+                config.code,
+                started.elapsed().as_secs_f64(),
+                err
+            );
+            RegionOutcome::Failed
+        }
+    }
+}
+
+async fn shutdown_requested(shutdown: &mut watch::Receiver<bool>) {
+    while !*shutdown.borrow() {
+        if shutdown.changed().await.is_err() {
+            // The signal task is gone; never resolve so the daemon keeps running.
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => info!("Received SIGINT"),
+                _ = terminate.recv() => info!("Received SIGTERM"),
+            }
+        }
+        Err(err) => {
+            warn!("Failed to install SIGTERM handler: {}", err);
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
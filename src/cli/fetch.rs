@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::config::{Settings, Workspace};
+use crate::error::{AppError, Result};
+use crate::fetch::FetchReport;
+use crate::records::StockDatabase;
+
+use super::open_region;
+
+/// Interval between progress lines while the snapshot fetch is running.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Symbols listed per failure cause in the text summary.
+const REPORT_SYMBOLS_SHOWN: usize = 5;
+
+/// Output style for headless fetch progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for ProgressFormat {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "text" => Ok(ProgressFormat::Text),
+            "json" => Ok(ProgressFormat::Json),
+            other => Err(AppError::message(format!(
+                "Invalid format '{}'. Expected text or json.",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    /// Region code; falls back to `default_region` from the settings file.
+    pub region: Option<String>,
+    pub format: ProgressFormat,
+    /// Continue from the region's fetch checkpoint when one exists.
+    pub resume: bool,
+}
+
+/// Snapshot a region without the TUI and persist the result through `Records`.
+pub async fn run_fetch(
+    workspace: &Workspace,
+    settings: &Settings,
+    options: FetchOptions,
+) -> Result<()> {
+    let mut region_state = open_region(workspace, settings, options.region.as_deref()).await?;
+    let region_code = region_state.config().code.clone();
+    let printer = ProgressPrinter {
+        format: options.format,
+        region: &region_code,
+    };
+
+    let resume = if options.resume {
+        region_state.resume_plan()?
+    } else {
+        None
+    };
+    let fetcher = match &resume {
+        Some(plan) => {
+            printer.resuming(plan.remaining.len(), plan.completed.len(), &plan.started_at);
+            region_state.resume_fetcher(plan)?
+        }
+        None => region_state.fresh_fetcher()?,
+    };
+    let progress = fetcher.progress_counter.clone();
+    let total = fetcher.total_stocks;
+    let mut handle = tokio::spawn(async move { fetcher.fetch_data().await });
+
+    let mut last_reported = None;
+    let outcome = loop {
+        tokio::select! {
+            result = &mut handle => break result?,
+            _ = tokio::time::sleep(PROGRESS_INTERVAL) => {
+                let done = progress.load(Ordering::SeqCst).min(total);
+                if last_reported != Some(done) {
+                    printer.progress(done, total);
+                    last_reported = Some(done);
+                }
+            }
+        }
+    };
+
+    match outcome {
+        Ok(snapshot) => {
+            let fetched = snapshot.data.len();
+            let saved_path = match resume {
+                Some(plan) => {
+                    // Merge onto the latest saved snapshot so rows from earlier runs survive.
+                    if let Some((path, _)) = region_state.records().latest_snapshot()? {
+                        let latest = region_state.records().load_snapshot(&path)?;
+                        region_state.replace_database(latest);
+                    }
+                    let mut rows = plan.completed;
+                    rows.extend(snapshot.data);
+                    region_state.merge_snapshot(rows)?
+                }
+                None => region_state
+                    .records()
+                    .save_snapshot(&StockDatabase::new(snapshot.data))?,
+            };
+            let report_path = region_state
+                .records()
+                .save_fetch_report(&saved_path, &snapshot.report)?;
+            region_state.settle_checkpoint(&snapshot.report)?;
+            printer.finished(
+                fetched,
+                total,
+                &saved_path.display().to_string(),
+                &snapshot.report,
+                &report_path.display().to_string(),
+            );
+            Ok(())
+        }
+        Err(err) => {
+            printer.failed(total, &err.to_string());
+            Err(err)
+        }
+    }
+}
+
+struct ProgressPrinter<'a> {
+    format: ProgressFormat,
+    region: &'a str,
+}
+
+impl ProgressPrinter<'_> {
+    fn progress(&self, done: usize, total: usize) {
+        match self.format {
+            ProgressFormat::Text => {
+                let percent = if total == 0 {
+                    0.0
+                } else {
+                    done as f64 / total as f64 * 100.0
+                };
+                println!("[{}] {}/{} ({:.0}%)", self.region, done, total, percent);
+            }
+            ProgressFormat::Json => println!(
+                "{}",
+                json!({ "event": "progress", "region": self.region, "done": done, "total": total })
+            ),
+        }
+    }
+
+    fn resuming(&self, remaining: usize, completed: usize, started_at: &str) {
+        match self.format {
+            ProgressFormat::Text => println!(
+                "[{}] Resuming fetch from {}: {} done, {} remaining",
+                self.region, started_at, completed, remaining
+            ),
+            ProgressFormat::Json => println!(
+                "{}",
+                json!({
+                    "event": "resuming",
+                    "region": self.region,
+                    "started_at": started_at,
+                    "completed": completed,
+                    "remaining": remaining,
+                })
+            ),
+        }
+    }
+
+    fn finished(
+        &self,
+        fetched: usize,
+        total: usize,
+        saved: &str,
+        report: &FetchReport,
+        report_path: &str,
+    ) {
+        match self.format {
+            ProgressFormat::Text => {
+                println!(
+                    "[{}] Fetched {} of {} symbols. Saved: {}",
+                    self.region, fetched, total, saved
+                );
+                for line in report.failure_lines(REPORT_SYMBOLS_SHOWN) {
+                    println!("[{}]   {}", self.region, line);
+                }
+                println!("[{}] Report: {}", self.region, report_path);
+            }
+            ProgressFormat::Json => {
+                let failures: BTreeMap<_, _> = report
+                    .failures
+                    .iter()
+                    .map(|(kind, failures)| (kind, failures.len()))
+                    .collect();
+                println!(
+                    "{}",
+                    json!({
+                        "event": "finished",
+                        "region": self.region,
+                        "fetched": fetched,
+                        "total": total,
+                        "saved": saved,
+                        "failed": report.failed_count(),
+                        "failures": failures,
+                        "report": report_path,
+                    })
+                )
+            }
+        }
+    }
+
+    fn failed(&self, total: usize, message: &str) {
+        match self.format {
+            ProgressFormat::Text => eprintln!("[{}] Fetch failed: {}", self.region, message),
+            ProgressFormat::Json => println!(
+                "{}",
+                json!({ "event": "failed", "region": self.region, "total": total, "error": message })
+            ),
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use log::warn;
+use serde::Serialize;
+
+use crate::config::{HistoryInterval, Settings, Workspace};
+use crate::error::{AppError, Context, Result};
+use crate::fetch::{aggregate_candles, fetch_history, AggregatePeriod, Candle};
+
+use super::load_region_config;
+
+const CANDLE_DATE_FMT: &str = "%Y-%m-%d";
+const CANDLE_DATETIME_FMT: &str = "%Y-%m-%d %H:%M";
+
+/// Output style for exported candles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryFormat {
+    Csv,
+    Json,
+}
+
+impl FromStr for HistoryFormat {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "csv" => Ok(HistoryFormat::Csv),
+            "json" => Ok(HistoryFormat::Json),
+            other => Err(AppError::message(format!(
+                "Invalid format '{}'. Expected csv or json.",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryOptions {
+    /// Region code; falls back to `default_region` from the settings file.
+    pub region: Option<String>,
+    pub symbols: Vec<String>,
+    /// File with one symbol per line (the first CSV column is used, `#` lines are skipped).
+    pub symbols_file: Option<PathBuf>,
+    pub interval: HistoryInterval,
+    /// Roll the downloaded candles up into weekly or monthly bars before export.
+    pub aggregate: Option<AggregatePeriod>,
+    pub limit: Option<usize>,
+    pub format: HistoryFormat,
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct SymbolHistory {
+    symbol: String,
+    candles: Vec<CandleRow>,
+}
+
+#[derive(Serialize)]
+struct CandleRow {
+    date: String,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    volume: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amount: Option<f64>,
+}
+
+impl CandleRow {
+    fn new(candle: &Candle, date_format: &str) -> Self {
+        Self {
+            date: candle.timestamp.format(date_format).to_string(),
+            open: candle.open,
+            high: candle.high,
+            low: candle.low,
+            close: candle.close,
+            volume: candle.volume,
+            amount: candle.amount,
+        }
+    }
+}
+
+/// Download candles of one interval for one or more symbols with the region's history config
+/// and export them.
+pub async fn run_history(
+    workspace: &Workspace,
+    settings: &Settings,
+    options: HistoryOptions,
+) -> Result<()> {
+    let region = load_region_config(workspace, settings, options.region.as_deref())?;
+    let symbols = collect_symbols(&options)?;
+
+    let date_format = if options.interval.is_intraday() && options.aggregate.is_none() {
+        CANDLE_DATETIME_FMT
+    } else {
+        CANDLE_DATE_FMT
+    };
+
+    // `--limit` counts output bars, so an aggregated export needs enough source candles to fill
+    // that many periods plus the leading one that is usually cut short.
+    let fetch_limit = match (options.aggregate, options.limit) {
+        (Some(period), Some(limit)) => Some(source_candles(period, options.interval, limit + 1)),
+        _ => options.limit,
+    };
+    let depth = fetch_limit.or_else(|| {
+        region
+            .provider
+            .history_for(options.interval)
+            .and_then(|history| history.limit)
+    });
+
+    let mut histories = Vec::with_capacity(symbols.len());
+    for symbol in symbols {
+        match fetch_history(&symbol, &region, options.interval, fetch_limit).await {
+            Ok(candles) => {
+                let candles = match options.aggregate {
+                    Some(period) => {
+                        let truncated = depth.is_some_and(|depth| candles.len() >= depth);
+                        aggregate_recent(&candles, period, truncated, options.limit)
+                    }
+                    None => candles,
+                };
+                histories.push(SymbolHistory {
+                    symbol,
+                    candles: candles
+                        .iter()
+                        .map(|candle| CandleRow::new(candle, date_format))
+                        .collect(),
+                });
+            }
+            Err(err) => warn!("History fetch failed for {}: {}", symbol, err),
+        }
+    }
+
+    if histories.is_empty() {
+        return Err(AppError::message("Failed to fetch history for any symbol"));
+    }
+
+    let mut out: Box<dyn Write> = match &options.output {
+        Some(path) => Box::new(
+            File::create(path)
+                .with_context(|| format!("Failed to create output file {}", path.display()))?,
+        ),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    match options.format {
+        HistoryFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut out);
+            writer.write_record([
+                "symbol", "date", "open", "high", "low", "close", "volume", "amount",
+            ])?;
+            for history in &histories {
+                for row in &history.candles {
+                    writer.write_record([
+                        history.symbol.as_str(),
+                        row.date.as_str(),
+                        &row.open.to_string(),
+                        &row.high.to_string(),
+                        &row.low.to_string(),
+                        &row.close.to_string(),
+                        &optional_field(row.volume),
+                        &optional_field(row.amount),
+                    ])?;
+                }
+            }
+            writer.flush()?;
+        }
+        HistoryFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &histories)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Source candles of `interval` that span at least `periods` aggregation periods.
+fn source_candles(period: AggregatePeriod, interval: HistoryInterval, periods: usize) -> usize {
+    let per_period = period
+        .max_span()
+        .as_secs()
+        .div_ceil(interval.period().as_secs().max(1)) as usize;
+    periods.saturating_mul(per_period.max(1))
+}
+
+/// Aggregate `candles` and keep the last `limit` bars. When the download was cut at its depth
+/// the first period is missing its earliest candles, so that partial bar is dropped.
+fn aggregate_recent(
+    candles: &[Candle],
+    period: AggregatePeriod,
+    truncated: bool,
+    limit: Option<usize>,
+) -> Vec<Candle> {
+    let mut bars = aggregate_candles(candles, period);
+    if truncated && !bars.is_empty() {
+        bars.remove(0);
+    }
+    if let Some(limit) = limit {
+        bars.drain(..bars.len().saturating_sub(limit));
+    }
+    bars
+}
+
+fn optional_field(value: Option<f64>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn collect_symbols(options: &HistoryOptions) -> Result<Vec<String>> {
+    let mut symbols = options.symbols.clone();
+
+    if let Some(path) = &options.symbols_file {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read symbols file {}", path.display()))?;
+        symbols.extend(
+            contents
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .filter_map(|line| line.split(',').next())
+                .map(|code| code.trim().to_string()),
+        );
+    }
+
+    let mut seen = HashSet::new();
+    symbols.retain(|code| seen.insert(code.clone()));
+    if symbols.is_empty() {
+        return Err(AppError::message(
+            "history requires at least one --symbol or a --symbols-file",
+        ));
+    }
+    Ok(symbols)
+}
@@ -0,0 +1,131 @@
+use std::str::FromStr;
+
+use log::warn;
+
+use crate::config::{HistoryInterval, Settings, Workspace};
+use crate::error::{AppError, Result};
+use crate::fetch::{rebuild_history, HistoryStore};
+
+use super::load_region_config;
+
+/// Maintenance applied to a region's on-disk history cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheAction {
+    /// Delete cached candles.
+    Purge,
+    /// Download the full history again and replace the cached candles with it.
+    Rebuild,
+}
+
+impl FromStr for CacheAction {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "purge" => Ok(CacheAction::Purge),
+            "rebuild" => Ok(CacheAction::Rebuild),
+            other => Err(AppError::message(format!(
+                "Invalid history-cache action '{}'. Expected purge or rebuild.",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryCacheOptions {
+    /// Region code; falls back to `default_region` from the settings file.
+    pub region: Option<String>,
+    pub action: CacheAction,
+    /// Symbols to act on; empty means every cached symbol.
+    pub symbols: Vec<String>,
+    /// Intervals to act on; empty means every interval the region declares.
+    pub intervals: Vec<HistoryInterval>,
+}
+
+/// Purge or rebuild the per-symbol history cache of a region.
+pub async fn run_history_cache(
+    workspace: &Workspace,
+    settings: &Settings,
+    options: HistoryCacheOptions,
+) -> Result<()> {
+    let region = load_region_config(workspace, settings, options.region.as_deref())?;
+    let intervals = if options.intervals.is_empty() {
+        region.provider.history_intervals()
+    } else {
+        options.intervals.clone()
+    };
+
+    let mut attempted = 0;
+    let mut rebuilt_total = 0;
+    for interval in intervals {
+        if region.provider.history_for(interval).is_none() {
+            return Err(AppError::message(format!(
+                "Region {} declares no `{}` history",
+                region.code,
+                interval.label()
+            )));
+        }
+        let store = HistoryStore::for_region(&region, interval);
+
+        match options.action {
+            CacheAction::Purge => {
+                let removed = if options.symbols.is_empty() {
+                    store.purge()?
+                } else {
+                    let mut removed = 0;
+                    for symbol in &options.symbols {
+                        if store.remove(symbol)? {
+                            removed += 1;
+                        }
+                    }
+                    removed
+                };
+                println!(
+                    "[{}] Removed {} cached `{}` symbol(s) from {}",
+                    region.code,
+                    removed,
+                    interval.label(),
+                    store.dir().display()
+                );
+            }
+            CacheAction::Rebuild => {
+                let symbols = if options.symbols.is_empty() {
+                    store.symbols()?
+                } else {
+                    options.symbols.clone()
+                };
+                let mut rebuilt = 0;
+                // The old cache is only replaced once its download succeeds.
+                for symbol in &symbols {
+                    match rebuild_history(symbol, &region, interval).await {
+                        Ok(_) => rebuilt += 1,
+                        Err(err) => warn!(
+                            "History rebuild failed for {} ({}): {}",
+                            symbol,
+                            interval.label(),
+                            err
+                        ),
+                    }
+                }
+                attempted += symbols.len();
+                rebuilt_total += rebuilt;
+                println!(
+                    "[{}] Rebuilt {} of {} cached `{}` symbol(s) in {}",
+                    region.code,
+                    rebuilt,
+                    symbols.len(),
+                    interval.label(),
+                    store.dir().display()
+                );
+            }
+        }
+    }
+
+    if attempted > 0 && rebuilt_total == 0 {
+        return Err(AppError::message(
+            "Failed to rebuild history for any symbol",
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! Headless subcommands that drive the fetch and records pipelines without the TUI.
+
+use std::sync::Arc;
+
+use crate::app::{market_registry::MarketRegistry, state::RegionState};
+use crate::config::{registry::ConfigRegistry, RegionConfig, Settings, Workspace};
+use crate::error::{AppError, Result};
+
+pub mod daemon;
+pub mod fetch;
+pub mod history;
+pub mod history_cache;
+pub mod screen;
+pub mod validate;
+
+pub use daemon::{run_daemon, DaemonOptions, DEFAULT_DAEMON_INTERVAL};
+pub use fetch::{run_fetch, FetchOptions, ProgressFormat};
+pub use history::{run_history, HistoryFormat, HistoryOptions};
+pub use history_cache::{run_history_cache, CacheAction, HistoryCacheOptions};
+pub use screen::{run_screen, ScreenFormat, ScreenOptions};
+pub use validate::{run_validate, ValidateOptions, INVALID_CONFIG_EXIT_CODE};
+
+/// Resolve the configuration for `region_code`, or the settings default region, from the
+/// workspace descriptors.
+fn load_region_config(
+    workspace: &Workspace,
+    settings: &Settings,
+    region_code: Option<&str>,
+) -> Result<RegionConfig> {
+    let region_code = region_code
+        .or(settings.default_region.as_deref())
+        .ok_or_else(|| {
+            AppError::message("--region <code> is required when settings has no default_region")
+        })?;
+    let registry = Arc::new(ConfigRegistry::new(workspace.clone())?);
+    MarketRegistry::new(registry, Arc::new(settings.clone())).ensure_region(region_code)
+}
+
+/// Load the descriptor for the requested region and prepare its stock list and storage
+/// directories.
+async fn open_region(
+    workspace: &Workspace,
+    settings: &Settings,
+    region_code: Option<&str>,
+) -> Result<RegionState> {
+    RegionState::new(load_region_config(workspace, settings, region_code)?).await
+}
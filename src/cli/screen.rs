@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::{Settings, Threshold, Workspace};
+use crate::error::{AppError, Result};
+use crate::fetch::StockData;
+use crate::records::{write_csv_rows, SortField};
+use crate::utils::resolve_named_file;
+
+use super::open_region;
+
+/// Output style for screened rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for ScreenFormat {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "table" => Ok(ScreenFormat::Table),
+            "csv" => Ok(ScreenFormat::Csv),
+            "json" => Ok(ScreenFormat::Json),
+            other => Err(AppError::message(format!(
+                "Invalid format '{}'. Expected table, csv, or json.",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScreenOptions {
+    /// Region code; falls back to `default_region` from the settings file.
+    pub region: Option<String>,
+    /// Preset name under the region filters directory, or a path to a preset JSON file.
+    pub preset: Option<String>,
+    /// Snapshot file name under the region snapshots directory, or a path to a CSV file.
+    pub snapshot: Option<String>,
+    pub format: ScreenFormat,
+    pub sort: SortField,
+    pub descending: bool,
+}
+
+/// Apply a threshold preset to a stored snapshot and print the matching rows.
+pub async fn run_screen(
+    workspace: &Workspace,
+    settings: &Settings,
+    options: ScreenOptions,
+) -> Result<()> {
+    let region_state = open_region(workspace, settings, options.region.as_deref()).await?;
+    let records = region_state.records();
+
+    let snapshot_path = match &options.snapshot {
+        Some(name) => resolve_named_file(records.snapshots_dir(), name, "csv"),
+        None => records
+            .latest_snapshot()?
+            .map(|(path, _)| path)
+            .ok_or_else(|| {
+                AppError::message(format!(
+                    "No snapshots found in {}. Run `stock-cli fetch --region {}` first.",
+                    records.snapshots_dir().display(),
+                    region_state.config().code
+                ))
+            })?,
+    };
+    let database = records.load_snapshot(&snapshot_path)?;
+
+    let thresholds: HashMap<String, Threshold> = match &options.preset {
+        Some(name) => records.load_threshold_preset(resolve_named_file(
+            records.presets_dir(),
+            name,
+            "json",
+        ))?,
+        None => region_state.thresholds().clone(),
+    };
+
+    let codes = database.filter_stocks(&thresholds);
+    let rows = database.sorted_rows(&codes, options.sort, options.descending);
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    match options.format {
+        ScreenFormat::Table => write_table(&mut out, &rows)?,
+        ScreenFormat::Csv => write_csv_rows(csv::Writer::from_writer(&mut out), rows)?,
+        ScreenFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &rows)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn write_table(out: &mut impl Write, rows: &[&StockData]) -> Result<()> {
+    let mut header = vec!["Stock Name".to_string(), "Code".to_string()];
+    header.extend(SortField::ALL.iter().map(|field| field.label().to_string()));
+
+    let body: Vec<Vec<String>> = rows
+        .iter()
+        .map(|stock| {
+            let mut cells = vec![stock.stock_name.clone(), stock.stock_code.clone()];
+            cells.extend(
+                [
+                    stock.curr,
+                    stock.prev_closed,
+                    stock.open,
+                    stock.increase,
+                    stock.highest,
+                    stock.lowest,
+                    stock.turn_over,
+                    stock.amp,
+                    stock.tm,
+                ]
+                .iter()
+                .map(|value| format!("{:.2}", value)),
+            );
+            cells
+        })
+        .collect();
+
+    let mut widths: Vec<usize> = header.iter().map(|cell| cell.width()).collect();
+    for cells in &body {
+        for (idx, cell) in cells.iter().enumerate() {
+            widths[idx] = widths[idx].max(cell.width());
+        }
+    }
+
+    for cells in std::iter::once(&header).chain(body.iter()) {
+        let line = cells
+            .iter()
+            .enumerate()
+            .map(|(idx, cell)| {
+                let padding = " ".repeat(widths[idx] - cell.width());
+                // Names and codes read left-aligned; numeric metrics align on the right.
+                if idx < 2 {
+                    format!("{}{}", cell, padding)
+                } else {
+                    format!("{}{}", padding, cell)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(out, "{}", line.trim_end())?;
+    }
+
+    writeln!(out, "{} matching rows", rows.len())?;
+    Ok(())
+}
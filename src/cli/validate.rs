@@ -0,0 +1,88 @@
+use std::process::ExitCode;
+
+use serde_json::json;
+
+use crate::config::validator::{validate_config_dir, FileReport, Severity};
+use crate::config::Workspace;
+use crate::error::Result;
+
+/// Exit status returned when at least one region config has errors.
+pub const INVALID_CONFIG_EXIT_CODE: u8 = 2;
+
+#[derive(Debug, Clone)]
+pub struct ValidateOptions {
+    pub json: bool,
+}
+
+/// Check every region config independently and print a per-file report.
+pub fn run_validate(workspace: &Workspace, options: ValidateOptions) -> Result<ExitCode> {
+    let reports = validate_config_dir(workspace)?;
+    let errors: usize = reports.iter().map(FileReport::error_count).sum();
+    let warnings: usize = reports.iter().map(FileReport::warning_count).sum();
+
+    if options.json {
+        let files = reports
+            .iter()
+            .map(|report| {
+                json!({
+                    "file": report.file,
+                    "region": report.region,
+                    "valid": report.error_count() == 0,
+                    "errors": report.error_count(),
+                    "warnings": report.warning_count(),
+                    "issues": report.issues,
+                })
+            })
+            .collect::<Vec<_>>();
+        let summary = json!({
+            "root": workspace.root(),
+            "configs_dir": workspace.configs_dir(),
+            "files": files,
+            "errors": errors,
+            "warnings": warnings,
+        });
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    } else {
+        print_text_report(&reports);
+        println!(
+            "{} file(s) checked: {} error(s), {} warning(s)",
+            reports.len(),
+            errors,
+            warnings
+        );
+    }
+
+    Ok(if errors > 0 {
+        ExitCode::from(INVALID_CONFIG_EXIT_CODE)
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn print_text_report(reports: &[FileReport]) {
+    for report in reports {
+        let label = match &report.region {
+            Some(code) => format!("{} ({})", report.file.display(), code),
+            None => report.file.display().to_string(),
+        };
+
+        if report.issues.is_empty() {
+            println!("{}: OK", label);
+            continue;
+        }
+
+        println!(
+            "{}: {} error(s), {} warning(s)",
+            label,
+            report.error_count(),
+            report.warning_count()
+        );
+        for issue in &report.issues {
+            let severity = match issue.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            println!("  {:<7} {}", severity, issue);
+        }
+    }
+}
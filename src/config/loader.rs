@@ -2,18 +2,23 @@ use std::{
     collections::HashMap,
     fs,
     path::{Path, PathBuf},
+    time::Duration,
 };
 
+use chrono::{FixedOffset, NaiveTime, Weekday};
 use serde::Deserialize;
 
 use crate::error::{AppError, Context, Result};
 
 use super::{
-    CodeTransform, CsvHistoryResponse, DelimitedResponseConfig, FirewallWarning, HistoryConfig,
-    HistoryFieldIndices, HistoryResponse, HttpMethod, InfoIndex, JsonHistoryResponse,
-    JsonHistoryRowFormat, JsonPathSegment, JsonResponseConfig, ProviderConfig, RegionStorage,
-    RequestConfig, SnapshotConfig, SnapshotResponse, StooqProviderConfig, TencentProviderConfig,
-    Threshold,
+    BodyContentType, CodeTransform, CsvHistoryResponse, DelimitedResponseConfig, FirewallWarning,
+    HistoryConfig, HistoryFieldIndices, HistoryFieldTransforms, HistoryInterval, HistoryResponse,
+    HttpMethod, InfoIndex, InfoSource, JsVarsResponseConfig, JsonHistoryResponse,
+    JsonHistoryRowFormat, JsonPath, JsonPathSegment, JsonResponseConfig, PredicateValue,
+    ProviderConfig, RateLimitConfig, RegexHistoryResponse, RegexResponseConfig, RegionStorage,
+    RequestBody, RequestConfig, ResponseEncoding, RetryErrorKind, RetryPolicy, SnapshotConfig,
+    SnapshotResponse, StooqProviderConfig, TencentProviderConfig, Threshold, TradingCalendar,
+    TradingSession, ValueTransform, Workspace,
 };
 use crate::config::validator;
 
@@ -28,14 +33,25 @@ pub struct RegionDescriptor {
     pub thresholds: HashMap<String, Threshold>,
     pub provider: ProviderConfig,
     pub storage: RegionStorage,
+    pub trading_sessions: Option<TradingCalendar>,
 }
 
 /// Load a region descriptor by combining the JSON market configuration with the stock list CSV.
-pub fn load_region_descriptor(root: &Path, region_slug: &str) -> Result<RegionDescriptor> {
-    let json_path = root
-        .join("assets")
-        .join("configs")
-        .join(format!("{region_slug}.json"));
+pub fn load_region_descriptor(
+    workspace: &Workspace,
+    region_slug: &str,
+) -> Result<RegionDescriptor> {
+    let descriptor = build_region_descriptor(workspace, region_slug)?;
+    validator::validate_region_descriptor(&descriptor)?;
+    Ok(descriptor)
+}
+
+/// Parse and resolve a region descriptor without running the validator checks.
+pub(crate) fn build_region_descriptor(
+    workspace: &Workspace,
+    region_slug: &str,
+) -> Result<RegionDescriptor> {
+    let json_path = workspace.configs_dir().join(format!("{region_slug}.json"));
 
     let json = fs::read_to_string(&json_path).with_context(|| {
         format!(
@@ -53,7 +69,7 @@ pub fn load_region_descriptor(root: &Path, region_slug: &str) -> Result<RegionDe
 
     ensure_region_code(&raw.code, region_slug, &json_path)?;
 
-    let stock_path = resolve_stock_path(root, &raw.stock_list)?;
+    let stock_path = resolve_stock_path(workspace, &raw.stock_list)?;
     let stock_codes = load_stock_codes(&stock_path)?;
 
     let thresholds = raw
@@ -65,9 +81,13 @@ pub fn load_region_descriptor(root: &Path, region_slug: &str) -> Result<RegionDe
     let provider = raw.provider.into_provider_config()?;
     let storage = raw
         .storage
-        .into_storage(root, &region_slug.to_lowercase())?;
+        .into_storage(workspace, &region_slug.to_lowercase())?;
+    let trading_sessions = raw
+        .trading_sessions
+        .map(RawTradingSessions::into_calendar)
+        .transpose()?;
 
-    let descriptor = RegionDescriptor {
+    Ok(RegionDescriptor {
         code: raw.code,
         name: raw.name,
         stock_list_file: stock_path,
@@ -75,23 +95,20 @@ pub fn load_region_descriptor(root: &Path, region_slug: &str) -> Result<RegionDe
         thresholds,
         provider,
         storage,
-    };
-
-    validator::validate_region_descriptor(&descriptor)?;
-
-    Ok(descriptor)
+        trading_sessions,
+    })
 }
 
-/// Discover and load every region descriptor under `assets/configs`.
+/// Discover and load every region descriptor in the workspace configs directory.
 #[allow(dead_code)]
-pub fn load_region_descriptors(root: &Path) -> Result<Vec<RegionDescriptor>> {
-    let configs_dir = root.join("assets").join("configs");
+pub fn load_region_descriptors(workspace: &Workspace) -> Result<Vec<RegionDescriptor>> {
+    let configs_dir = workspace.configs_dir();
     if !configs_dir.exists() {
         return Ok(Vec::new());
     }
 
     let mut descriptors = Vec::new();
-    for entry in fs::read_dir(&configs_dir).with_context(|| {
+    for entry in fs::read_dir(configs_dir).with_context(|| {
         format!(
             "failed to read region config directory {}",
             configs_dir.display()
@@ -106,28 +123,29 @@ pub fn load_region_descriptors(root: &Path) -> Result<Vec<RegionDescriptor>> {
             Some(slug) => slug.to_string(),
             None => continue,
         };
-        descriptors.push(load_region_descriptor(root, &slug)?);
+        descriptors.push(load_region_descriptor(workspace, &slug)?);
     }
 
     descriptors.sort_by(|a, b| a.code.cmp(&b.code));
     Ok(descriptors)
 }
 
-fn resolve_stock_path(root: &Path, stock_list: &RawStockList) -> Result<PathBuf> {
+fn resolve_stock_path(workspace: &Workspace, stock_list: &RawStockList) -> Result<PathBuf> {
     if stock_list.file.trim().is_empty() {
-        return Err(AppError::message(
-            "stock_list.file must be provided in region config JSON",
+        return Err(AppError::invalid_config(
+            "stock_list.file",
+            "must be provided in region config JSON",
         ));
     }
 
-    let path = root.join(&stock_list.file);
+    let path = workspace.resolve_path(&stock_list.file);
     if path.exists() {
         Ok(path)
     } else {
-        Err(AppError::message(format!(
-            "stock list file not found: {}",
-            path.display()
-        )))
+        Err(AppError::invalid_config(
+            "stock_list.file",
+            format!("stock list file not found: {}", path.display()),
+        ))
     }
 }
 
@@ -151,12 +169,15 @@ fn ensure_region_code(actual: &str, expected_slug: &str, source: &Path) -> Resul
     if normalised_actual == normalised_expected {
         Ok(())
     } else {
-        Err(AppError::message(format!(
-            "region code mismatch in {}: expected `{}`, found `{}`",
-            source.display(),
-            normalised_expected,
-            actual
-        )))
+        Err(AppError::invalid_config(
+            "code",
+            format!(
+                "region code mismatch in {}: expected `{}`, found `{}`",
+                source.display(),
+                normalised_expected,
+                actual
+            ),
+        ))
     }
 }
 
@@ -171,6 +192,8 @@ struct RawRegionConfig {
     provider: RawProviderConfig,
     #[serde(default)]
     storage: RawStorageConfig,
+    #[serde(default)]
+    trading_sessions: Option<RawTradingSessions>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -202,28 +225,66 @@ enum RawProviderConfig {
     Tencent {
         snapshot: RawSnapshotConfig,
         history: RawHistoryConfig,
+        #[serde(default)]
+        history_intervals: Vec<RawHistoryConfig>,
+        #[serde(default)]
+        rate_limit: Option<RawRateLimit>,
     },
     Stooq {
         snapshot: RawSnapshotConfig,
         history: RawHistoryConfig,
+        #[serde(default)]
+        history_intervals: Vec<RawHistoryConfig>,
+        #[serde(default)]
+        rate_limit: Option<RawRateLimit>,
     },
 }
 
 impl RawProviderConfig {
     fn into_provider_config(self) -> Result<ProviderConfig> {
         match self {
-            RawProviderConfig::Tencent { snapshot, history } => {
-                Ok(ProviderConfig::Tencent(TencentProviderConfig {
-                    snapshot: snapshot.into_snapshot_config()?,
-                    history: history.into_history_config()?,
-                }))
-            }
-            RawProviderConfig::Stooq { snapshot, history } => {
-                Ok(ProviderConfig::Stooq(StooqProviderConfig {
-                    snapshot: snapshot.into_snapshot_config()?,
-                    history: history.into_history_config()?,
-                }))
-            }
+            RawProviderConfig::Tencent {
+                snapshot,
+                history,
+                history_intervals,
+                rate_limit,
+            } => Ok(ProviderConfig::Tencent(TencentProviderConfig {
+                snapshot: snapshot.into_snapshot_config()?,
+                history: history.into_history_config("provider.history")?,
+                history_intervals: into_history_intervals(history_intervals)?,
+                rate_limit: rate_limit.map(RawRateLimit::into_rate_limit),
+            })),
+            RawProviderConfig::Stooq {
+                snapshot,
+                history,
+                history_intervals,
+                rate_limit,
+            } => Ok(ProviderConfig::Stooq(StooqProviderConfig {
+                snapshot: snapshot.into_snapshot_config()?,
+                history: history.into_history_config("provider.history")?,
+                history_intervals: into_history_intervals(history_intervals)?,
+                rate_limit: rate_limit.map(RawRateLimit::into_rate_limit),
+            })),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRateLimit {
+    requests_per_second: f64,
+    #[serde(default = "default_rate_limit_burst")]
+    burst: usize,
+}
+
+fn default_rate_limit_burst() -> usize {
+    1
+}
+
+impl RawRateLimit {
+    fn into_rate_limit(self) -> RateLimitConfig {
+        RateLimitConfig {
+            requests_per_second: self.requests_per_second,
+            burst: self.burst,
         }
     }
 }
@@ -231,92 +292,169 @@ impl RawProviderConfig {
 #[derive(Debug, Deserialize)]
 struct RawSnapshotConfig {
     request: RawRequestConfig,
-    response: RawSnapshotResponse,
+    response: RawResponseSection<RawSnapshotResponse>,
     #[serde(default)]
     firewall_warning: Option<String>,
     #[serde(default)]
-    info_indices: HashMap<String, usize>,
+    info_indices: HashMap<String, RawInfoIndex>,
+    #[serde(default)]
+    batch_size: Option<usize>,
 }
 
 impl RawSnapshotConfig {
     fn into_snapshot_config(self) -> Result<SnapshotConfig> {
-        let request = self.request.into_request()?;
-        let response = self.response.into_response()?;
-        let info_idxs = self
+        let request = self
+            .request
+            .into_request("provider.snapshot.request", RetryPolicy::snapshot_default())?;
+        let encoding = self.response.encoding("provider.snapshot.response")?;
+        let response = self
+            .response
+            .format
+            .into_response("provider.snapshot.response")?;
+        let mut info_idxs = self
             .info_indices
             .into_iter()
             .map(|(key, index)| {
-                let info_index = InfoIndex { index };
-                (key, info_index)
+                let info_index =
+                    index.into_info_index(&format!("provider.snapshot.info_indices.{key}"))?;
+                Ok((key, info_index))
             })
-            .collect();
+            .collect::<Result<HashMap<_, _>>>()?;
+        // Named capture groups feed the snapshot field of the same name unless mapped explicitly.
+        if let SnapshotResponse::Regex(regex) = &response {
+            let compiled = compile_pattern(regex, "provider.snapshot.response")?;
+            for (index, name) in compiled.capture_names().enumerate() {
+                if let Some(name) = name {
+                    info_idxs.entry(name.to_string()).or_insert(InfoIndex {
+                        source: InfoSource::Position(index),
+                        transform: ValueTransform::default(),
+                    });
+                }
+            }
+        }
 
         let firewall_warning = self.firewall_warning.map(|text| FirewallWarning { text });
 
+        let batch_size = self.batch_size.unwrap_or(1);
+        if batch_size == 0 {
+            return Err(AppError::invalid_config(
+                "provider.snapshot.batch_size",
+                "must be at least 1",
+            ));
+        }
+
         Ok(SnapshotConfig {
             request,
             response,
             info_idxs,
             firewall_warning,
+            batch_size,
+            encoding,
         })
     }
 }
 
 #[derive(Debug, Deserialize)]
 struct RawHistoryConfig {
+    #[serde(default)]
+    interval: Option<String>,
     request: RawRequestConfig,
-    response: RawHistoryResponse,
+    response: RawResponseSection<RawHistoryResponse>,
     #[serde(default)]
     limit: Option<usize>,
 }
 
 impl RawHistoryConfig {
-    fn into_history_config(self) -> Result<HistoryConfig> {
+    fn into_history_config(self, path_prefix: &str) -> Result<HistoryConfig> {
+        let interval = match self.interval.as_deref() {
+            None => HistoryInterval::Day,
+            Some(label) => HistoryInterval::from_label(label).ok_or_else(|| {
+                AppError::invalid_config(
+                    format!("{path_prefix}.interval"),
+                    format!("unsupported interval `{label}` (expected 1m, 5m, 30m, day, week, or month)"),
+                )
+            })?,
+        };
         Ok(HistoryConfig {
-            request: self.request.into_request()?,
-            response: self.response.into_history_response()?,
+            interval,
+            request: self.request.into_request(
+                &format!("{path_prefix}.request"),
+                RetryPolicy::history_default(),
+            )?,
+            encoding: self.response.encoding(&format!("{path_prefix}.response"))?,
+            response: self
+                .response
+                .format
+                .into_history_response(&format!("{path_prefix}.response"))?,
             limit: self.limit,
         })
     }
 }
 
+fn into_history_intervals(raw: Vec<RawHistoryConfig>) -> Result<Vec<HistoryConfig>> {
+    raw.into_iter()
+        .enumerate()
+        .map(|(idx, history)| {
+            let prefix = format!("provider.history_intervals[{idx}]");
+            if history.interval.is_none() {
+                return Err(AppError::invalid_config(
+                    format!("{prefix}.interval"),
+                    "is required for additional history intervals",
+                ));
+            }
+            history.into_history_config(&prefix)
+        })
+        .collect()
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 enum RawHistoryResponse {
     JsonRows {
-        path: Vec<String>,
+        path: RawJsonPath,
+        #[serde(alias = "datetime_format")]
         date_format: String,
         columns: RawHistoryColumns,
         #[serde(default)]
         row: RawJsonHistoryRowFormat,
+        #[serde(default)]
+        jsonp: bool,
     },
     CsvRows {
         delimiter: String,
         skip_lines: usize,
+        #[serde(alias = "datetime_format")]
         date_format: String,
         columns: RawHistoryColumns,
     },
+    Regex {
+        pattern: String,
+        #[serde(alias = "datetime_format")]
+        date_format: String,
+        /// Capture group numbers; defaults to the groups named after each column.
+        #[serde(default)]
+        columns: Option<RawHistoryColumns>,
+    },
 }
 
 impl RawHistoryResponse {
-    fn into_history_response(self) -> Result<HistoryResponse> {
+    fn into_history_response(self, path_prefix: &str) -> Result<HistoryResponse> {
         match self {
             RawHistoryResponse::JsonRows {
                 path,
                 date_format,
                 columns,
                 row,
+                jsonp,
             } => {
-                let segments = path
-                    .into_iter()
-                    .map(parse_json_path_segment)
-                    .collect::<Result<Vec<_>>>()?;
-                let indices = columns.into_indices();
-                let row_format = row.into_row_format(indices)?;
+                let segments = parse_json_path(path, path_prefix)?;
+                let indices = columns.into_indices(&format!("{path_prefix}.columns"))?;
+                let row_format = row.into_row_format(indices, path_prefix)?;
                 Ok(HistoryResponse::JsonRows(JsonHistoryResponse {
                     data_path: segments,
                     row_format,
                     date_format,
+                    jsonp,
                 }))
             }
             RawHistoryResponse::CsvRows {
@@ -326,13 +464,33 @@ impl RawHistoryResponse {
                 columns,
             } => {
                 let character = delimiter.chars().next().ok_or_else(|| {
-                    AppError::message("history.response.csv delimiter must not be empty")
+                    AppError::invalid_config(
+                        format!("{path_prefix}.delimiter"),
+                        "must not be empty",
+                    )
                 })?;
 
                 Ok(HistoryResponse::CsvRows(CsvHistoryResponse {
                     delimiter: character,
                     skip_lines,
-                    indices: columns.into_indices(),
+                    indices: columns.into_indices(&format!("{path_prefix}.columns"))?,
+                    date_format,
+                }))
+            }
+            RawHistoryResponse::Regex {
+                pattern,
+                date_format,
+                columns,
+            } => {
+                let pattern = RegexResponseConfig { pattern };
+                let compiled = compile_pattern(&pattern, path_prefix)?;
+                let indices = match columns {
+                    Some(columns) => columns.into_indices(&format!("{path_prefix}.columns"))?,
+                    None => named_group_indices(&compiled, path_prefix)?,
+                };
+                Ok(HistoryResponse::RegexRows(RegexHistoryResponse {
+                    pattern,
+                    indices,
                     date_format,
                 }))
             }
@@ -343,24 +501,96 @@ impl RawHistoryResponse {
 #[derive(Debug, Deserialize)]
 struct RawHistoryColumns {
     date: usize,
-    open: usize,
-    high: usize,
-    low: usize,
-    close: usize,
+    open: RawColumn,
+    high: RawColumn,
+    low: RawColumn,
+    close: RawColumn,
+    #[serde(default)]
+    volume: Option<RawColumn>,
+    #[serde(default)]
+    amount: Option<RawColumn>,
+}
+
+/// Numeric history column: a bare index or an object with an `index` and value transforms.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawColumn {
+    Index(usize),
+    Detailed {
+        index: usize,
+        #[serde(flatten)]
+        transform: RawValueTransform,
+    },
+}
+
+impl RawColumn {
+    fn into_column(self, path_prefix: &str) -> Result<(usize, ValueTransform)> {
+        match self {
+            RawColumn::Index(index) => Ok((index, ValueTransform::default())),
+            RawColumn::Detailed { index, transform } => {
+                Ok((index, transform.into_transform(path_prefix)?))
+            }
+        }
+    }
 }
 
 impl RawHistoryColumns {
-    fn into_indices(self) -> HistoryFieldIndices {
-        HistoryFieldIndices {
+    fn into_indices(self, path_prefix: &str) -> Result<HistoryFieldIndices> {
+        let column = |raw: RawColumn, name: &str| raw.into_column(&format!("{path_prefix}.{name}"));
+        let optional =
+            |raw: Option<RawColumn>, name: &str| raw.map(|raw| column(raw, name)).transpose();
+        let (open, open_transform) = column(self.open, "open")?;
+        let (high, high_transform) = column(self.high, "high")?;
+        let (low, low_transform) = column(self.low, "low")?;
+        let (close, close_transform) = column(self.close, "close")?;
+        let volume = optional(self.volume, "volume")?;
+        let amount = optional(self.amount, "amount")?;
+        Ok(HistoryFieldIndices {
             date: self.date,
-            open: self.open,
-            high: self.high,
-            low: self.low,
-            close: self.close,
-        }
+            open,
+            high,
+            low,
+            close,
+            volume: volume.as_ref().map(|(index, _)| *index),
+            amount: amount.as_ref().map(|(index, _)| *index),
+            transforms: HistoryFieldTransforms {
+                open: open_transform,
+                high: high_transform,
+                low: low_transform,
+                close: close_transform,
+                volume: volume.map(|(_, transform)| transform).unwrap_or_default(),
+                amount: amount.map(|(_, transform)| transform).unwrap_or_default(),
+            },
+        })
     }
 }
 
+/// Column indices taken from capture groups named `date`, `open`, `high`, `low`, `close`, and
+/// optionally `volume` and `amount`.
+fn named_group_indices(regex: &regex::Regex, path_prefix: &str) -> Result<HistoryFieldIndices> {
+    let group = |name: &str| regex.capture_names().position(|group| group == Some(name));
+    let required = |name: &str| {
+        group(name).ok_or_else(|| {
+            AppError::invalid_config(
+                format!("{path_prefix}.pattern"),
+                format!(
+                    "must name a `{name}` capture group or the response must declare `columns`"
+                ),
+            )
+        })
+    };
+    Ok(HistoryFieldIndices {
+        date: required("date")?,
+        open: required("open")?,
+        high: required("high")?,
+        low: required("low")?,
+        close: required("close")?,
+        volume: group("volume"),
+        amount: group("amount"),
+        transforms: HistoryFieldTransforms::default(),
+    })
+}
+
 #[derive(Debug, Deserialize, Default)]
 struct RawJsonHistoryRowFormat {
     delimiter: Option<String>,
@@ -370,46 +600,148 @@ struct RawJsonHistoryRowFormat {
 struct RawStorageConfig {
     snapshots_dir: Option<String>,
     filters_dir: Option<String>,
+    history_dir: Option<String>,
 }
 
 impl RawStorageConfig {
-    fn into_storage(self, root: &Path, slug: &str) -> Result<RegionStorage> {
+    fn into_storage(self, workspace: &Workspace, slug: &str) -> Result<RegionStorage> {
         let snapshots = self
             .snapshots_dir
             .unwrap_or_else(|| format!("assets/snapshots/{slug}"));
         let filters = self
             .filters_dir
             .unwrap_or_else(|| format!("assets/filters/{slug}"));
+        let history = self
+            .history_dir
+            .unwrap_or_else(|| format!("assets/history/{slug}"));
 
         if snapshots.trim().is_empty() {
-            return Err(AppError::message("storage.snapshots_dir must not be empty"));
+            return Err(AppError::invalid_config(
+                "storage.snapshots_dir",
+                "must not be empty",
+            ));
         }
 
         if filters.trim().is_empty() {
-            return Err(AppError::message("storage.filters_dir must not be empty"));
+            return Err(AppError::invalid_config(
+                "storage.filters_dir",
+                "must not be empty",
+            ));
+        }
+
+        if history.trim().is_empty() {
+            return Err(AppError::invalid_config(
+                "storage.history_dir",
+                "must not be empty",
+            ));
         }
 
         Ok(RegionStorage {
-            snapshots_dir: normalize_path(root, snapshots),
-            filters_dir: normalize_path(root, filters),
+            snapshots_dir: workspace.resolve_path(snapshots),
+            filters_dir: workspace.resolve_path(filters),
+            history_dir: workspace.resolve_path(history),
         })
     }
 }
 
-fn normalize_path(root: &Path, value: String) -> PathBuf {
-    let path = PathBuf::from(&value);
-    if path.is_absolute() {
-        path
-    } else {
-        root.join(path)
+#[derive(Debug, Deserialize)]
+struct RawTradingSessions {
+    utc_offset: String,
+    #[serde(default = "default_trading_weekdays")]
+    weekdays: Vec<String>,
+    sessions: Vec<RawTradingSession>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawTradingSession {
+    start: String,
+    end: String,
+}
+
+fn default_trading_weekdays() -> Vec<String> {
+    ["mon", "tue", "wed", "thu", "fri"]
+        .iter()
+        .map(|day| day.to_string())
+        .collect()
+}
+
+impl RawTradingSessions {
+    fn into_calendar(self) -> Result<TradingCalendar> {
+        let utc_offset = parse_utc_offset(&self.utc_offset).ok_or_else(|| {
+            AppError::invalid_config(
+                "trading_sessions.utc_offset",
+                format!("expected `+HH:MM` or `-HH:MM`, found `{}`", self.utc_offset),
+            )
+        })?;
+
+        let weekdays = self
+            .weekdays
+            .iter()
+            .enumerate()
+            .map(|(idx, day)| {
+                day.parse::<Weekday>().map_err(|_| {
+                    AppError::invalid_config(
+                        format!("trading_sessions.weekdays[{idx}]"),
+                        format!("unknown weekday `{day}`"),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let sessions = self
+            .sessions
+            .into_iter()
+            .enumerate()
+            .map(|(idx, session)| {
+                let prefix = format!("trading_sessions.sessions[{idx}]");
+                Ok(TradingSession {
+                    start: parse_session_time(&session.start, &format!("{prefix}.start"))?,
+                    end: parse_session_time(&session.end, &format!("{prefix}.end"))?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(TradingCalendar {
+            utc_offset,
+            weekdays,
+            sessions,
+        })
+    }
+}
+
+fn parse_utc_offset(value: &str) -> Option<FixedOffset> {
+    let value = value.trim();
+    let (sign, rest) = match value.chars().next()? {
+        '+' => (1, &value[1..]),
+        '-' => (-1, &value[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
     }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+fn parse_session_time(value: &str, path: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| AppError::invalid_config(path, format!("expected `HH:MM`, found `{value}`")))
 }
 
 impl RawJsonHistoryRowFormat {
-    fn into_row_format(self, indices: HistoryFieldIndices) -> Result<JsonHistoryRowFormat> {
+    fn into_row_format(
+        self,
+        indices: HistoryFieldIndices,
+        path_prefix: &str,
+    ) -> Result<JsonHistoryRowFormat> {
         if let Some(delimiter) = self.delimiter {
             let ch = delimiter.chars().next().ok_or_else(|| {
-                AppError::message("history.response.json_rows.row.delimiter must not be empty")
+                AppError::invalid_config(
+                    format!("{path_prefix}.row.delimiter"),
+                    "must not be empty",
+                )
             })?;
             Ok(JsonHistoryRowFormat::StringDelimited {
                 delimiter: ch,
@@ -429,18 +761,115 @@ struct RawRequestConfig {
     headers: HashMap<String, String>,
     #[serde(default)]
     code_transform: RawCodeTransform,
+    #[serde(default)]
+    symbols_joiner: Option<String>,
+    #[serde(default)]
+    body: Option<RawRequestBody>,
+    #[serde(default)]
+    retry: RawRetryPolicy,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRequestBody {
+    #[serde(default = "default_body_content_type")]
+    content_type: String,
+    template: serde_json::Value,
+}
+
+fn default_body_content_type() -> String {
+    "json".to_string()
+}
+
+impl RawRequestBody {
+    fn into_body(self, path_prefix: &str) -> Result<RequestBody> {
+        let content_type = match self.content_type.as_str() {
+            "json" => BodyContentType::Json,
+            "form" => BodyContentType::Form,
+            other => {
+                return Err(AppError::invalid_config(
+                    format!("{path_prefix}.body.content_type"),
+                    format!("unsupported content type `{other}`; expected `json` or `form`"),
+                ))
+            }
+        };
+
+        Ok(RequestBody {
+            content_type,
+            template: self.template,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct RawRetryPolicy {
+    max_attempts: Option<usize>,
+    base_delay_ms: Option<u64>,
+    max_delay_ms: Option<u64>,
+    jitter: Option<f64>,
+    retry_statuses: Option<Vec<u16>>,
+    retry_on: Option<Vec<String>>,
+}
+
+impl RawRetryPolicy {
+    fn into_policy(self, defaults: RetryPolicy, path_prefix: &str) -> Result<RetryPolicy> {
+        let retry_on = match self.retry_on {
+            Some(kinds) => kinds
+                .iter()
+                .enumerate()
+                .map(|(idx, kind)| {
+                    RetryErrorKind::from_label(kind).ok_or_else(|| {
+                        AppError::invalid_config(
+                            format!("{path_prefix}.retry.retry_on[{idx}]"),
+                            format!(
+                                "unknown error kind `{kind}`; expected one of {}",
+                                RetryErrorKind::ALL
+                                    .iter()
+                                    .map(|kind| kind.label())
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => defaults.retry_on,
+        };
+
+        Ok(RetryPolicy {
+            max_attempts: self.max_attempts.unwrap_or(defaults.max_attempts),
+            base_delay: self
+                .base_delay_ms
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.base_delay),
+            max_delay: self
+                .max_delay_ms
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.max_delay),
+            jitter: self.jitter.unwrap_or(defaults.jitter),
+            retry_statuses: self.retry_statuses.unwrap_or(defaults.retry_statuses),
+            retry_on,
+        })
+    }
 }
 
 impl RawRequestConfig {
-    fn into_request(self) -> Result<RequestConfig> {
-        let method = parse_method(&self.method)?;
-        let code_transform = self.code_transform.into_code_transform()?;
+    fn into_request(self, path_prefix: &str, default_retry: RetryPolicy) -> Result<RequestConfig> {
+        let method = parse_method(&self.method, path_prefix)?;
+        let code_transform = self.code_transform.into_code_transform().map_err(|err| {
+            AppError::invalid_config(format!("{path_prefix}.code_transform"), err.to_string())
+        })?;
 
         Ok(RequestConfig {
             method,
             url_template: self.url_template,
             headers: self.headers,
             code_transform,
+            symbols_joiner: self.symbols_joiner.unwrap_or_else(|| ",".to_string()),
+            body: self
+                .body
+                .map(|body| body.into_body(path_prefix))
+                .transpose()?,
+            retry: self.retry.into_policy(default_retry, path_prefix)?,
         })
     }
 }
@@ -510,30 +939,63 @@ impl RawCodeTransform {
     }
 }
 
+/// Response block shared by snapshot and history endpoints: the body encoding next to the
+/// type-tagged payload format.
+#[derive(Debug, Deserialize)]
+struct RawResponseSection<F> {
+    #[serde(default)]
+    encoding: Option<String>,
+    #[serde(flatten)]
+    format: F,
+}
+
+impl<F> RawResponseSection<F> {
+    fn encoding(&self, path_prefix: &str) -> Result<Option<ResponseEncoding>> {
+        self.encoding
+            .as_deref()
+            .map(|label| {
+                ResponseEncoding::from_label(label).ok_or_else(|| {
+                    AppError::invalid_config(
+                        format!("{path_prefix}.encoding"),
+                        format!("unsupported encoding `{label}` (expected a label such as utf-8, gbk, or gb18030)"),
+                    )
+                })
+            })
+            .transpose()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 enum RawSnapshotResponse {
     JsonPath {
-        path: Vec<String>,
+        path: RawJsonPath,
+        #[serde(default)]
+        jsonp: bool,
     },
     Delimited {
         delimiter: String,
         #[serde(default)]
         skip_lines: usize,
     },
+    JsVars {
+        variable: String,
+        delimiter: String,
+    },
+    Regex {
+        pattern: String,
+    },
 }
 
 impl RawSnapshotResponse {
-    fn into_response(self) -> Result<SnapshotResponse> {
+    fn into_response(self, path_prefix: &str) -> Result<SnapshotResponse> {
         match self {
-            RawSnapshotResponse::JsonPath { path } => {
-                let segments = path
-                    .into_iter()
-                    .map(parse_json_path_segment)
-                    .collect::<Result<Vec<_>>>()?;
+            RawSnapshotResponse::JsonPath { path, jsonp } => {
+                let segments = parse_json_path(path, path_prefix)?;
 
                 Ok(SnapshotResponse::Json(JsonResponseConfig {
                     data_path: segments,
+                    jsonp,
                 }))
             }
             RawSnapshotResponse::Delimited {
@@ -541,7 +1003,10 @@ impl RawSnapshotResponse {
                 skip_lines,
             } => {
                 let character = delimiter.chars().next().ok_or_else(|| {
-                    AppError::message("delimiter cannot be empty in delimited response")
+                    AppError::invalid_config(
+                        format!("{path_prefix}.delimiter"),
+                        "must not be empty",
+                    )
                 })?;
 
                 Ok(SnapshotResponse::Delimited(DelimitedResponseConfig {
@@ -549,27 +1014,315 @@ impl RawSnapshotResponse {
                     skip_lines,
                 }))
             }
+            RawSnapshotResponse::JsVars {
+                variable,
+                delimiter,
+            } => {
+                let character = delimiter.chars().next().ok_or_else(|| {
+                    AppError::invalid_config(
+                        format!("{path_prefix}.delimiter"),
+                        "must not be empty",
+                    )
+                })?;
+
+                Ok(SnapshotResponse::JsVars(JsVarsResponseConfig {
+                    variable,
+                    delimiter: character,
+                }))
+            }
+            RawSnapshotResponse::Regex { pattern } => {
+                let regex = RegexResponseConfig { pattern };
+                compile_pattern(&regex, path_prefix)?;
+                Ok(SnapshotResponse::Regex(regex))
+            }
+        }
+    }
+}
+
+/// Compile `pattern` with a sample symbol so syntax errors surface when the descriptor loads.
+fn compile_pattern(pattern: &RegexResponseConfig, path_prefix: &str) -> Result<regex::Regex> {
+    pattern
+        .compile("SYMBOL")
+        .map_err(|err| AppError::invalid_config(format!("{path_prefix}.pattern"), err.to_string()))
+}
+
+/// Snapshot metric mapping: a bare source, or an object naming the source (`index` or `path`)
+/// next to value transforms.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawInfoIndex {
+    Source(RawInfoSource),
+    Detailed {
+        #[serde(default)]
+        index: Option<usize>,
+        #[serde(default)]
+        path: Option<RawInfoSource>,
+        #[serde(flatten)]
+        transform: RawValueTransform,
+    },
+}
+
+/// Snapshot metric source: a position, a key of a JSON object node, or a path inside it.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawInfoSource {
+    Position(usize),
+    Key(String),
+    Path(RawJsonPath),
+}
+
+impl RawInfoIndex {
+    fn into_info_index(self, path_prefix: &str) -> Result<InfoIndex> {
+        match self {
+            RawInfoIndex::Source(source) => Ok(InfoIndex {
+                source: source.into_source(path_prefix)?,
+                transform: ValueTransform::default(),
+            }),
+            RawInfoIndex::Detailed {
+                index,
+                path,
+                transform,
+            } => {
+                let source = match (index, path) {
+                    (Some(index), None) => InfoSource::Position(index),
+                    (None, Some(path)) => path.into_source(&format!("{path_prefix}.path"))?,
+                    _ => {
+                        return Err(AppError::invalid_config(
+                            path_prefix.to_string(),
+                            "must set exactly one of `index` or `path`",
+                        ))
+                    }
+                };
+                Ok(InfoIndex {
+                    source,
+                    transform: transform.into_transform(path_prefix)?,
+                })
+            }
+        }
+    }
+}
+
+impl RawInfoSource {
+    fn into_source(self, path_prefix: &str) -> Result<InfoSource> {
+        match self {
+            RawInfoSource::Position(index) => Ok(InfoSource::Position(index)),
+            RawInfoSource::Key(key) => {
+                let segment = parse_json_path_segment(key).map_err(|err| {
+                    AppError::invalid_config(path_prefix.to_string(), err.to_string())
+                })?;
+                Ok(InfoSource::Path(JsonPath {
+                    alternatives: vec![vec![segment]],
+                }))
+            }
+            RawInfoSource::Path(path) => {
+                Ok(InfoSource::Path(parse_json_path_at(path, path_prefix)?))
+            }
         }
     }
 }
 
+/// Value transforms shared by snapshot `info_indices` and history `columns` entries.
+#[derive(Debug, Default, Deserialize)]
+struct RawValueTransform {
+    #[serde(default)]
+    scale: Option<f64>,
+    #[serde(default)]
+    offset: Option<f64>,
+    #[serde(default)]
+    percent: bool,
+    #[serde(default)]
+    thousands_separator: Option<String>,
+    #[serde(default)]
+    missing: Vec<String>,
+    #[serde(default)]
+    default: Option<f64>,
+}
+
+impl RawValueTransform {
+    fn into_transform(self, path_prefix: &str) -> Result<ValueTransform> {
+        let thousands_separator = match self.thousands_separator {
+            None => None,
+            Some(separator) => {
+                let mut chars = separator.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => Some(ch),
+                    _ => {
+                        return Err(AppError::invalid_config(
+                            format!("{path_prefix}.thousands_separator"),
+                            "must be a single character",
+                        ))
+                    }
+                }
+            }
+        };
+        let scale = self.scale.unwrap_or(1.0);
+        if !scale.is_finite() || scale == 0.0 {
+            return Err(AppError::invalid_config(
+                format!("{path_prefix}.scale"),
+                "must be a finite, non-zero number",
+            ));
+        }
+        Ok(ValueTransform {
+            scale,
+            offset: self.offset.unwrap_or(0.0),
+            percent: self.percent,
+            thousands_separator,
+            missing: self
+                .missing
+                .into_iter()
+                .map(|sentinel| sentinel.trim().to_string())
+                .collect(),
+            default: self.default,
+        })
+    }
+}
+
+/// A JSON path is either one list of segments or a list of alternative segment lists.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawJsonPath {
+    Single(Vec<String>),
+    Alternatives(Vec<Vec<String>>),
+}
+
+fn parse_json_path(path: RawJsonPath, path_prefix: &str) -> Result<JsonPath> {
+    parse_json_path_at(path, &format!("{path_prefix}.path"))
+}
+
+/// Parse `path`, reporting errors against `location` itself.
+fn parse_json_path_at(path: RawJsonPath, location: &str) -> Result<JsonPath> {
+    let alternatives = match path {
+        RawJsonPath::Single(segments) => vec![parse_json_path_segments(segments, location)?],
+        RawJsonPath::Alternatives(paths) => paths
+            .into_iter()
+            .enumerate()
+            .map(|(alt, segments)| {
+                parse_json_path_segments(segments, &format!("{location}[{alt}]"))
+            })
+            .collect::<Result<_>>()?,
+    };
+    Ok(JsonPath { alternatives })
+}
+
+fn parse_json_path_segments(path: Vec<String>, path_prefix: &str) -> Result<Vec<JsonPathSegment>> {
+    path.into_iter()
+        .enumerate()
+        .map(|(idx, value)| {
+            parse_json_path_segment(value).map_err(|err| {
+                AppError::invalid_config(format!("{path_prefix}[{idx}]"), err.to_string())
+            })
+        })
+        .collect()
+}
+
 fn parse_json_path_segment(value: String) -> Result<JsonPathSegment> {
     if value == "{symbol}" {
-        Ok(JsonPathSegment::StockCode)
-    } else if value.is_empty() {
-        Err(AppError::message(
+        return Ok(JsonPathSegment::StockCode);
+    }
+    if value == "*" {
+        return Ok(JsonPathSegment::Wildcard);
+    }
+    if value.is_empty() {
+        return Err(AppError::message(
             "json_path entries must not be empty strings",
-        ))
-    } else {
-        Ok(JsonPathSegment::Key(value))
+        ));
     }
+
+    let Some(inner) = value
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    else {
+        return Ok(JsonPathSegment::Key(value));
+    };
+
+    if let Some(predicate) = inner.strip_prefix('?') {
+        let (field, expected) = predicate.split_once('=').ok_or_else(|| {
+            AppError::message(format!(
+                "predicate `{value}` must have the form `[?field=value]`"
+            ))
+        })?;
+        let field = field.trim();
+        if field.is_empty() {
+            return Err(AppError::message(format!(
+                "predicate `{value}` must name a field"
+            )));
+        }
+        let expected = expected.trim().trim_matches(|ch| ch == '"' || ch == '\'');
+        let value = if expected == "{symbol}" {
+            PredicateValue::StockCode
+        } else {
+            PredicateValue::Literal(expected.to_string())
+        };
+        return Ok(JsonPathSegment::Predicate {
+            field: field.to_string(),
+            value,
+        });
+    }
+
+    inner
+        .trim()
+        .parse::<isize>()
+        .map(JsonPathSegment::Index)
+        .map_err(|_| {
+            AppError::message(format!(
+                "`{value}` must be an array index such as `[0]` or a predicate such as `[?code={{symbol}}]`"
+            ))
+        })
 }
 
-fn parse_method(value: &str) -> Result<HttpMethod> {
+fn parse_method(value: &str, path_prefix: &str) -> Result<HttpMethod> {
     match value {
         "GET" | "get" => Ok(HttpMethod::Get),
-        other => Err(AppError::message(format!(
-            "unsupported HTTP method `{other}` in snapshot request"
-        ))),
+        "POST" | "post" => Ok(HttpMethod::Post),
+        "PUT" | "put" => Ok(HttpMethod::Put),
+        other => Err(AppError::invalid_config(
+            format!("{path_prefix}.method"),
+            format!("unsupported HTTP method `{other}`"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(value: &str) -> JsonPathSegment {
+        parse_json_path_segment(value.to_string()).unwrap()
+    }
+
+    #[test]
+    fn bracketed_segments_are_indices_or_predicates() {
+        assert!(matches!(segment("[0]"), JsonPathSegment::Index(0)));
+        assert!(matches!(segment("[ -1 ]"), JsonPathSegment::Index(-1)));
+        assert!(matches!(
+            segment("[?code={symbol}]"),
+            JsonPathSegment::Predicate { field, value: PredicateValue::StockCode } if field == "code"
+        ));
+        assert!(matches!(
+            segment("[? market = 'SH' ]"),
+            JsonPathSegment::Predicate { field, value: PredicateValue::Literal(expected) }
+                if field == "market" && expected == "SH"
+        ));
+    }
+
+    #[test]
+    fn other_segments_are_keys_unless_reserved() {
+        assert!(matches!(segment("{symbol}"), JsonPathSegment::StockCode));
+        assert!(matches!(segment("*"), JsonPathSegment::Wildcard));
+        // Brackets only count when they wrap the whole segment.
+        assert!(matches!(segment("rows[0]"), JsonPathSegment::Key(key) if key == "rows[0]"));
+    }
+
+    #[test]
+    fn malformed_segments_explain_the_expected_form() {
+        let err = |value: &str| {
+            parse_json_path_segment(value.to_string())
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(err("").contains("must not be empty"));
+        assert!(err("[1.5]").contains("array index"));
+        assert!(err("[?code]").contains("[?field=value]"));
+        assert!(err("[?=600000]").contains("must name a field"));
     }
 }
@@ -1,14 +1,67 @@
+use chrono::{DateTime, Datelike, FixedOffset, NaiveTime, TimeZone, Weekday};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 pub mod loader;
 pub mod registry;
+pub mod settings;
 pub mod validator;
+pub mod workspace;
 
+/// A snapshot metric mapping: where the value is read from and how it is normalised.
 #[derive(Debug, Clone)]
 pub struct InfoIndex {
-    pub index: usize,
+    pub source: InfoSource,
+    pub transform: ValueTransform,
+}
+
+/// Where a snapshot metric is read from inside the per-symbol payload.
+#[derive(Debug, Clone)]
+pub enum InfoSource {
+    /// Position in an array node, delimited row, or list of capture groups.
+    Position(usize),
+    /// Key or sub-path inside a JSON object node.
+    Path(JsonPath),
+}
+
+/// Normalisation applied to a raw field before it is parsed as a number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueTransform {
+    /// Multiplier applied to the parsed value, e.g. `100000000` for figures quoted in 亿.
+    pub scale: f64,
+    /// Added after scaling.
+    pub offset: f64,
+    /// Strip a trailing `%` before parsing.
+    pub percent: bool,
+    /// Grouping character removed before parsing, such as `,` in `1,234.5`.
+    pub thousands_separator: Option<char>,
+    /// Raw values treated as missing, such as `-` or `--`.
+    pub missing: Vec<String>,
+    /// Value used when the field is absent, empty, or a missing sentinel.
+    pub default: Option<f64>,
+}
+
+impl Default for ValueTransform {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            offset: 0.0,
+            percent: false,
+            thousands_separator: None,
+            missing: Vec::new(),
+            default: None,
+        }
+    }
+}
+
+impl ValueTransform {
+    /// Whether `raw` stands for "no value" rather than a number.
+    pub fn is_missing(&self, raw: &str) -> bool {
+        let raw = raw.trim();
+        raw.is_empty() || self.missing.iter().any(|sentinel| sentinel == raw)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,9 +71,52 @@ pub struct Threshold {
     pub valid: bool,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HttpMethod {
     Get,
+    Post,
+    Put,
+}
+
+impl HttpMethod {
+    pub fn label(self) -> &'static str {
+        match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+        }
+    }
+}
+
+/// Encoding applied to a rendered request body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyContentType {
+    Json,
+    Form,
+}
+
+impl BodyContentType {
+    pub fn label(self) -> &'static str {
+        match self {
+            BodyContentType::Json => "json",
+            BodyContentType::Form => "form",
+        }
+    }
+
+    pub fn mime(self) -> &'static str {
+        match self {
+            BodyContentType::Json => "application/json",
+            BodyContentType::Form => "application/x-www-form-urlencoded",
+        }
+    }
+}
+
+/// Body sent with POST/PUT requests. Every string inside `template` is rendered with the same
+/// placeholders as the URL template before the value is encoded.
+#[derive(Debug, Clone)]
+pub struct RequestBody {
+    pub content_type: BodyContentType,
+    pub template: serde_json::Value,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -58,6 +154,93 @@ pub struct RequestConfig {
     pub url_template: String,
     pub headers: HashMap<String, String>,
     pub code_transform: CodeTransform,
+    /// Separator placed between transformed codes when rendering `{symbols}`.
+    pub symbols_joiner: String,
+    pub body: Option<RequestBody>,
+    pub retry: RetryPolicy,
+}
+
+/// Transport failures that a retry policy can opt into retrying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryErrorKind {
+    Timeout,
+    Connect,
+    Request,
+    Body,
+}
+
+impl RetryErrorKind {
+    pub const ALL: [RetryErrorKind; 4] = [
+        RetryErrorKind::Timeout,
+        RetryErrorKind::Connect,
+        RetryErrorKind::Request,
+        RetryErrorKind::Body,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RetryErrorKind::Timeout => "timeout",
+            RetryErrorKind::Connect => "connect",
+            RetryErrorKind::Request => "request",
+            RetryErrorKind::Body => "body",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.label().eq_ignore_ascii_case(label))
+    }
+}
+
+/// How often and how patiently a request is retried.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: usize,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Fraction of each delay (0.0-1.0) randomly added or removed.
+    pub jitter: f64,
+    pub retry_statuses: Vec<u16>,
+    pub retry_on: Vec<RetryErrorKind>,
+}
+
+impl RetryPolicy {
+    /// Default for snapshot requests: three attempts with exponential backoff from 2s.
+    pub fn snapshot_default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_secs(2),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.0,
+            retry_statuses: vec![408, 425, 429, 500, 502, 503, 504],
+            retry_on: RetryErrorKind::ALL.to_vec(),
+        }
+    }
+
+    /// Default for history requests: a single attempt, matching the interactive chart.
+    pub fn history_default() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::snapshot_default()
+        }
+    }
+
+    pub fn retries_status(&self, status: u16) -> bool {
+        self.retry_statuses.contains(&status)
+    }
+
+    pub fn retries_error(&self, kind: RetryErrorKind) -> bool {
+        self.retry_on.contains(&kind)
+    }
+}
+
+/// Token bucket shared by every request sent to a provider host.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitConfig {
+    pub requests_per_second: f64,
+    /// Requests that may be sent back to back before the steady rate applies.
+    pub burst: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -71,6 +254,10 @@ pub struct SnapshotConfig {
     pub response: SnapshotResponse,
     pub info_idxs: HashMap<String, InfoIndex>,
     pub firewall_warning: Option<FirewallWarning>,
+    /// Number of symbols combined into one request; `1` disables batching.
+    pub batch_size: usize,
+    /// Character set of the response body; `None` follows the `Content-Type` charset.
+    pub encoding: Option<ResponseEncoding>,
 }
 
 #[derive(Debug, Clone)]
@@ -78,17 +265,73 @@ pub enum SnapshotResponse {
     Json(JsonResponseConfig),
     #[allow(dead_code)]
     Delimited(DelimitedResponseConfig),
+    JsVars(JsVarsResponseConfig),
+    Regex(RegexResponseConfig),
 }
 
 #[derive(Debug, Clone)]
 pub struct JsonResponseConfig {
-    pub data_path: Vec<JsonPathSegment>,
+    pub data_path: JsonPath,
+    /// Strip a `callback(...)` wrapper before parsing.
+    pub jsonp: bool,
+}
+
+/// Location of a payload inside a JSON document: one or more alternative segment lists, tried
+/// in order until one resolves.
+#[derive(Debug, Clone)]
+pub struct JsonPath {
+    pub alternatives: Vec<Vec<JsonPathSegment>>,
+}
+
+impl JsonPath {
+    pub fn is_empty(&self) -> bool {
+        self.alternatives.iter().all(Vec::is_empty)
+    }
+
+    /// Whether every alternative selects by the stock code.
+    pub fn references_symbol(&self) -> bool {
+        !self.alternatives.is_empty()
+            && self
+                .alternatives
+                .iter()
+                .all(|segments| segments.iter().any(JsonPathSegment::references_symbol))
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum JsonPathSegment {
     Key(String),
     StockCode,
+    /// `[n]`: array element; negative values count back from the end.
+    Index(isize),
+    /// `*`: any array element or object member; the first one the rest of the path resolves in
+    /// wins.
+    Wildcard,
+    /// `[?field=value]`: first array element whose `field` equals `value`.
+    Predicate {
+        field: String,
+        value: PredicateValue,
+    },
+}
+
+impl JsonPathSegment {
+    pub fn references_symbol(&self) -> bool {
+        matches!(
+            self,
+            JsonPathSegment::StockCode
+                | JsonPathSegment::Predicate {
+                    value: PredicateValue::StockCode,
+                    ..
+                }
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PredicateValue {
+    Literal(String),
+    /// `{symbol}`: the raw or transformed stock code.
+    StockCode,
 }
 
 #[derive(Debug, Clone)]
@@ -97,10 +340,131 @@ pub struct DelimitedResponseConfig {
     pub skip_lines: usize,
 }
 
+/// Script-style payload such as `v_sh600000="1~name~...";`, one quoted variable per symbol.
+#[derive(Debug, Clone)]
+pub struct JsVarsResponseConfig {
+    /// Variable name template; `{symbol}` is replaced by the transformed stock code.
+    pub variable: String,
+    pub delimiter: char,
+}
+
+impl JsVarsResponseConfig {
+    pub fn variable_for(&self, transformed_code: &str) -> String {
+        self.variable.replace("{symbol}", transformed_code)
+    }
+}
+
+/// Regular expression matched against a text payload; its capture groups, by group number,
+/// form the value row.
+#[derive(Debug, Clone)]
+pub struct RegexResponseConfig {
+    /// Pattern source; `{symbol}` is replaced by the escaped transformed stock code.
+    pub pattern: String,
+}
+
+impl RegexResponseConfig {
+    pub fn compile(&self, transformed_code: &str) -> Result<regex::Regex, regex::Error> {
+        regex::Regex::new(
+            &self
+                .pattern
+                .replace("{symbol}", &regex::escape(transformed_code)),
+        )
+    }
+}
+
+/// Character set a provider encodes response bodies in, such as GBK for legacy quote feeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseEncoding(&'static encoding_rs::Encoding);
+
+impl ResponseEncoding {
+    /// Look up a WHATWG encoding label such as `utf-8`, `gbk`, `gb18030`, or `big5`.
+    pub fn from_label(label: &str) -> Option<Self> {
+        encoding_rs::Encoding::for_label(label.trim().as_bytes()).map(Self)
+    }
+
+    pub fn name(self) -> &'static str {
+        self.0.name()
+    }
+
+    /// Decode `bytes`, replacing malformed sequences with U+FFFD.
+    pub fn decode(self, bytes: &[u8]) -> String {
+        let (text, _, _) = self.0.decode(bytes);
+        text.into_owned()
+    }
+}
+
+/// Candle granularity served by a history endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum HistoryInterval {
+    Minute1,
+    Minute5,
+    Minute30,
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl HistoryInterval {
+    pub const ALL: [HistoryInterval; 6] = [
+        HistoryInterval::Minute1,
+        HistoryInterval::Minute5,
+        HistoryInterval::Minute30,
+        HistoryInterval::Day,
+        HistoryInterval::Week,
+        HistoryInterval::Month,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HistoryInterval::Minute1 => "1m",
+            HistoryInterval::Minute5 => "5m",
+            HistoryInterval::Minute30 => "30m",
+            HistoryInterval::Day => "day",
+            HistoryInterval::Week => "week",
+            HistoryInterval::Month => "month",
+        }
+    }
+
+    pub fn from_label(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|interval| {
+            interval.label() == value
+                || match interval {
+                    HistoryInterval::Day => value == "1d",
+                    HistoryInterval::Week => value == "1w",
+                    HistoryInterval::Month => value == "1mo",
+                    _ => false,
+                }
+        })
+    }
+
+    /// Nominal length of one candle.
+    pub fn period(self) -> Duration {
+        const MINUTE: u64 = 60;
+        const DAY: u64 = 24 * 60 * MINUTE;
+        Duration::from_secs(match self {
+            HistoryInterval::Minute1 => MINUTE,
+            HistoryInterval::Minute5 => 5 * MINUTE,
+            HistoryInterval::Minute30 => 30 * MINUTE,
+            HistoryInterval::Day => DAY,
+            HistoryInterval::Week => 7 * DAY,
+            HistoryInterval::Month => 30 * DAY,
+        })
+    }
+
+    pub fn is_intraday(self) -> bool {
+        self < HistoryInterval::Day
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct HistoryConfig {
+    pub interval: HistoryInterval,
     pub request: RequestConfig,
     pub response: HistoryResponse,
+    /// Character set of the response body; `None` follows the `Content-Type` charset.
+    pub encoding: Option<ResponseEncoding>,
     pub limit: Option<usize>,
 }
 
@@ -109,13 +473,29 @@ pub enum HistoryResponse {
     JsonRows(JsonHistoryResponse),
     #[allow(dead_code)]
     CsvRows(CsvHistoryResponse),
+    RegexRows(RegexHistoryResponse),
+}
+
+impl HistoryResponse {
+    pub fn indices(&self) -> &HistoryFieldIndices {
+        match self {
+            HistoryResponse::JsonRows(cfg) => match &cfg.row_format {
+                JsonHistoryRowFormat::Array(indices) => indices,
+                JsonHistoryRowFormat::StringDelimited { indices, .. } => indices,
+            },
+            HistoryResponse::CsvRows(cfg) => &cfg.indices,
+            HistoryResponse::RegexRows(cfg) => &cfg.indices,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct JsonHistoryResponse {
-    pub data_path: Vec<JsonPathSegment>,
+    pub data_path: JsonPath,
     pub row_format: JsonHistoryRowFormat,
     pub date_format: String,
+    /// Strip a `callback(...)` wrapper before parsing.
+    pub jsonp: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -135,6 +515,14 @@ pub struct CsvHistoryResponse {
     pub date_format: String,
 }
 
+/// One candle per pattern match; `indices` refer to capture group numbers.
+#[derive(Debug, Clone)]
+pub struct RegexHistoryResponse {
+    pub pattern: RegexResponseConfig,
+    pub indices: HistoryFieldIndices,
+    pub date_format: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct HistoryFieldIndices {
     pub date: usize,
@@ -142,24 +530,89 @@ pub struct HistoryFieldIndices {
     pub high: usize,
     pub low: usize,
     pub close: usize,
+    /// Traded volume column, when the provider reports one.
+    pub volume: Option<usize>,
+    /// Traded amount (turnover) column, when the provider reports one.
+    pub amount: Option<usize>,
+    pub transforms: HistoryFieldTransforms,
+}
+
+/// Value transforms for the numeric history columns.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFieldTransforms {
+    pub open: ValueTransform,
+    pub high: ValueTransform,
+    pub low: ValueTransform,
+    pub close: ValueTransform,
+    pub volume: ValueTransform,
+    pub amount: ValueTransform,
 }
 
 #[derive(Debug, Clone)]
 pub struct RegionStorage {
     pub snapshots_dir: PathBuf,
     pub filters_dir: PathBuf,
+    /// Per-symbol candle cache read before any history request.
+    pub history_dir: PathBuf,
+}
+
+/// One continuous trading window, in the calendar's local time. A session whose `end` is not
+/// after its `start` runs past midnight into the next day.
+#[derive(Debug, Clone)]
+pub struct TradingSession {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TradingSession {
+    pub fn is_overnight(&self) -> bool {
+        self.end <= self.start
+    }
+}
+
+/// Weekly trading hours of a region, expressed at a fixed UTC offset.
+#[derive(Debug, Clone)]
+pub struct TradingCalendar {
+    pub utc_offset: FixedOffset,
+    pub weekdays: Vec<Weekday>,
+    pub sessions: Vec<TradingSession>,
+}
+
+impl TradingCalendar {
+    /// Whether `at` falls inside one of the sessions on a trading weekday. Overnight sessions
+    /// belong to the weekday they start on, so their after-midnight part follows that day.
+    pub fn is_open<Tz: TimeZone>(&self, at: &DateTime<Tz>) -> bool {
+        let local = at.with_timezone(&self.utc_offset);
+        let today = local.weekday();
+        let trades = |day: Weekday| self.weekdays.contains(&day);
+        let time = local.time();
+        self.sessions.iter().any(|session| {
+            if session.is_overnight() {
+                (trades(today) && time >= session.start)
+                    || (trades(today.pred()) && time < session.end)
+            } else {
+                trades(today) && session.start <= time && time < session.end
+            }
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct TencentProviderConfig {
     pub snapshot: SnapshotConfig,
     pub history: HistoryConfig,
+    /// Additional granularities, each with its own endpoint.
+    pub history_intervals: Vec<HistoryConfig>,
+    pub rate_limit: Option<RateLimitConfig>,
 }
 
 #[derive(Debug, Clone)]
 pub struct StooqProviderConfig {
     pub snapshot: SnapshotConfig,
     pub history: HistoryConfig,
+    /// Additional granularities, each with its own endpoint.
+    pub history_intervals: Vec<HistoryConfig>,
+    pub rate_limit: Option<RateLimitConfig>,
 }
 
 #[derive(Debug, Clone)]
@@ -183,6 +636,35 @@ impl ProviderConfig {
             ProviderConfig::Stooq(cfg) => &cfg.history,
         }
     }
+
+    /// The primary history config followed by the additional intervals.
+    pub fn histories(&self) -> impl Iterator<Item = &HistoryConfig> {
+        let (primary, extra) = match self {
+            ProviderConfig::Tencent(cfg) => (&cfg.history, &cfg.history_intervals),
+            ProviderConfig::Stooq(cfg) => (&cfg.history, &cfg.history_intervals),
+        };
+        std::iter::once(primary).chain(extra.iter())
+    }
+
+    /// History config for `interval`, if the region declares one.
+    pub fn history_for(&self, interval: HistoryInterval) -> Option<&HistoryConfig> {
+        self.histories().find(|cfg| cfg.interval == interval)
+    }
+
+    /// Declared intervals from finest to coarsest.
+    pub fn history_intervals(&self) -> Vec<HistoryInterval> {
+        let mut intervals: Vec<_> = self.histories().map(|cfg| cfg.interval).collect();
+        intervals.sort();
+        intervals.dedup();
+        intervals
+    }
+
+    pub fn rate_limit(&self) -> Option<&RateLimitConfig> {
+        match self {
+            ProviderConfig::Tencent(cfg) => cfg.rate_limit.as_ref(),
+            ProviderConfig::Stooq(cfg) => cfg.rate_limit.as_ref(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -193,6 +675,13 @@ pub struct RegionConfig {
     pub thresholds: HashMap<String, Threshold>,
     pub provider: ProviderConfig,
     pub storage: RegionStorage,
+    pub trading_sessions: Option<TradingCalendar>,
+    /// Fetch tuning resolved from the user settings file.
+    pub fetch: FetchTuning,
+    /// Proxy for provider requests, resolved from the user settings file.
+    pub proxy: Option<ProxyConfig>,
+    /// Threshold preset applied when the region is opened.
+    pub default_preset: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -204,14 +693,17 @@ pub struct Config {
 pub use loader::{load_region_descriptor, load_region_descriptors, RegionDescriptor};
 #[allow(unused_imports)]
 pub use registry::ConfigRegistry;
+pub use settings::{FetchOverrides, FetchTuning, ProxyConfig, Settings};
 #[allow(unused_imports)]
 pub use validator::{validate_region_descriptor, validate_region_descriptors};
+pub use workspace::Workspace;
 
 impl Config {
     #[allow(dead_code)]
     pub fn builtin() -> Self {
-        let root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-        let descriptors = load_region_descriptors(&root).unwrap_or_default();
+        let workspace =
+            Workspace::resolve(None, None).unwrap_or_else(|_| Workspace::new(PathBuf::from(".")));
+        let descriptors = load_region_descriptors(&workspace).unwrap_or_default();
         let regions = descriptors
             .iter()
             .map(|descriptor| {
@@ -245,6 +737,68 @@ impl From<&RegionDescriptor> for RegionConfig {
             thresholds: descriptor.thresholds.clone(),
             provider: descriptor.provider.clone(),
             storage: descriptor.storage.clone(),
+            trading_sessions: descriptor.trading_sessions.clone(),
+            fetch: FetchTuning::default(),
+            proxy: None,
+            default_preset: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calendar(sessions: &[(&str, &str)]) -> TradingCalendar {
+        let time = |value: &str| NaiveTime::parse_from_str(value, "%H:%M").unwrap();
+        TradingCalendar {
+            utc_offset: FixedOffset::east_opt(8 * 3600).unwrap(),
+            weekdays: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            sessions: sessions
+                .iter()
+                .map(|(start, end)| TradingSession {
+                    start: time(start),
+                    end: time(end),
+                })
+                .collect(),
+        }
+    }
+
+    fn open_at(calendar: &TradingCalendar, value: &str) -> bool {
+        let at = DateTime::parse_from_str(&format!("{value} +08:00"), "%Y-%m-%d %H:%M %z").unwrap();
+        calendar.is_open(&at)
+    }
+
+    #[test]
+    fn day_sessions_are_half_open_on_weekdays() {
+        let calendar = calendar(&[("09:30", "11:30"), ("13:00", "15:00")]);
+        // 2026-10-12 is a Monday.
+        assert!(!open_at(&calendar, "2026-10-12 09:29"));
+        assert!(open_at(&calendar, "2026-10-12 09:30"));
+        assert!(!open_at(&calendar, "2026-10-12 11:30"));
+        assert!(!open_at(&calendar, "2026-10-12 12:00"));
+        assert!(open_at(&calendar, "2026-10-12 14:59"));
+        assert!(!open_at(&calendar, "2026-10-17 10:00"));
+    }
+
+    #[test]
+    fn night_sessions_belong_to_the_day_they_start() {
+        let calendar = calendar(&[("21:00", "02:30")]);
+        assert!(calendar.sessions[0].is_overnight());
+        assert!(open_at(&calendar, "2026-10-12 21:00"));
+        assert!(open_at(&calendar, "2026-10-13 01:00"));
+        assert!(!open_at(&calendar, "2026-10-13 02:30"));
+
+        // Monday's early hours continue Sunday, which does not trade, while Saturday's continue
+        // Friday's session.
+        assert!(!open_at(&calendar, "2026-10-12 01:00"));
+        assert!(open_at(&calendar, "2026-10-17 01:00"));
+        assert!(!open_at(&calendar, "2026-10-17 21:00"));
+    }
+}
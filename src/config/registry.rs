@@ -1,6 +1,5 @@
 use std::{
     collections::HashMap,
-    path::{Path, PathBuf},
     sync::{Arc, Mutex, RwLock},
 };
 
@@ -11,6 +10,7 @@ use tokio::sync::watch;
 use crate::error::{AppError, Result};
 
 use super::loader::{load_region_descriptors, RegionDescriptor};
+use super::Workspace;
 
 #[derive(Clone)]
 struct RegistryState {
@@ -36,21 +36,18 @@ impl RegistryState {
 
 /// Central cache of market descriptors loaded from disk, with optional file watching.
 pub struct ConfigRegistry {
-    root: PathBuf,
+    workspace: Workspace,
     state: RwLock<RegistryState>,
     updates_tx: watch::Sender<Arc<Vec<RegionDescriptor>>>,
     watcher: Mutex<Option<RecommendedWatcher>>,
 }
 
 impl ConfigRegistry {
-    /// Build the registry by scanning `assets/configs` under the provided root.
-    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
-        let root = root.into();
-        let descriptors = load_region_descriptors(&root)?;
+    /// Build the registry by scanning the workspace configs directory.
+    pub fn new(workspace: Workspace) -> Result<Self> {
+        let descriptors = load_region_descriptors(&workspace)?;
         if descriptors.is_empty() {
-            return Err(AppError::message(
-                "No region descriptors found under assets/configs",
-            ));
+            return Err(no_descriptors_error(&workspace));
         }
 
         let state = RegistryState::from_descriptors(descriptors);
@@ -58,7 +55,7 @@ impl ConfigRegistry {
         let (updates_tx, _) = watch::channel(view.clone());
 
         Ok(Self {
-            root,
+            workspace,
             state: RwLock::new(state),
             updates_tx,
             watcher: Mutex::new(None),
@@ -84,11 +81,9 @@ impl ConfigRegistry {
 
     /// Force a reload from disk and broadcast updates when data changes.
     pub fn refresh(&self) -> Result<()> {
-        let descriptors = load_region_descriptors(self.root())?;
+        let descriptors = load_region_descriptors(&self.workspace)?;
         if descriptors.is_empty() {
-            return Err(AppError::message(
-                "No region descriptors found under assets/configs",
-            ));
+            return Err(no_descriptors_error(&self.workspace));
         }
 
         let new_state = RegistryState::from_descriptors(descriptors);
@@ -108,7 +103,7 @@ impl ConfigRegistry {
             return Ok(());
         }
 
-        let configs_dir = self.configs_dir();
+        let configs_dir = self.workspace.configs_dir().to_path_buf();
         if !configs_dir.exists() {
             std::fs::create_dir_all(&configs_dir).map_err(AppError::from)?;
         }
@@ -135,13 +130,17 @@ impl ConfigRegistry {
         Ok(())
     }
 
-    fn root(&self) -> &Path {
-        &self.root
+    /// Directories this registry was loaded from.
+    pub fn workspace(&self) -> &Workspace {
+        &self.workspace
     }
+}
 
-    fn configs_dir(&self) -> PathBuf {
-        self.root().join("assets").join("configs")
-    }
+fn no_descriptors_error(workspace: &Workspace) -> AppError {
+    AppError::message(format!(
+        "No region descriptors found under {}",
+        workspace.configs_dir().display()
+    ))
 }
 
 fn is_relevant_event(kind: &EventKind) -> bool {
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::error::{AppError, Context, Result};
+use crate::fetch::{
+    DEFAULT_HISTORY_MAX_AGE_SECS, DEFAULT_HISTORY_TIMEOUT_SECS, DEFAULT_RESUME_MAX_AGE_SECS,
+    SNAPSHOT_CONCURRENCY_LIMIT,
+};
+
+use super::{RegionConfig, Workspace};
+
+/// File name of the user settings file, looked up in the workspace root.
+pub const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// Network knobs applied to a region's snapshot and history fetches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchTuning {
+    pub concurrency: usize,
+    /// Overrides the snapshot request's `retry.max_attempts` when set.
+    pub max_attempts: Option<usize>,
+    pub history_timeout: Duration,
+    /// Cached candles younger than this are served without contacting the provider.
+    pub history_max_age: Duration,
+    /// Fetch checkpoints older than this are discarded instead of resumed.
+    pub resume_max_age: Duration,
+}
+
+impl Default for FetchTuning {
+    fn default() -> Self {
+        Self {
+            concurrency: SNAPSHOT_CONCURRENCY_LIMIT,
+            max_attempts: None,
+            history_timeout: Duration::from_secs(DEFAULT_HISTORY_TIMEOUT_SECS),
+            history_max_age: Duration::from_secs(DEFAULT_HISTORY_MAX_AGE_SECS),
+            resume_max_age: Duration::from_secs(DEFAULT_RESUME_MAX_AGE_SECS),
+        }
+    }
+}
+
+/// Optional fetch values; unset fields inherit from the next broader scope.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FetchOverrides {
+    pub concurrency: Option<usize>,
+    pub max_attempts: Option<usize>,
+    pub history_timeout_secs: Option<u64>,
+    pub history_max_age_secs: Option<u64>,
+    pub resume_max_age_secs: Option<u64>,
+}
+
+impl FetchOverrides {
+    fn apply(&self, tuning: &mut FetchTuning) {
+        if let Some(concurrency) = self.concurrency {
+            tuning.concurrency = concurrency.max(1);
+        }
+        if let Some(max_attempts) = self.max_attempts {
+            tuning.max_attempts = Some(max_attempts.max(1));
+        }
+        if let Some(secs) = self.history_timeout_secs {
+            tuning.history_timeout = Duration::from_secs(secs.max(1));
+        }
+        if let Some(secs) = self.history_max_age_secs {
+            tuning.history_max_age = Duration::from_secs(secs);
+        }
+        if let Some(secs) = self.resume_max_age_secs {
+            tuning.resume_max_age = Duration::from_secs(secs);
+        }
+    }
+}
+
+/// Outbound HTTP/SOCKS proxy for a region's provider requests. Any field may reference
+/// `${ENV}` variables; they are expanded when the HTTP client is built.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProxyConfig {
+    /// `http://`, `https://`, `socks5://`, or `socks5h://` proxy URL.
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Hosts, domains, or IP ranges contacted directly.
+    pub no_proxy: Vec<String>,
+}
+
+/// Optional proxy values; unset fields inherit from the next broader scope.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProxyOverrides {
+    /// `false` turns off a proxy inherited from the global section.
+    pub enabled: Option<bool>,
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub no_proxy: Option<Vec<String>>,
+}
+
+impl ProxyOverrides {
+    fn apply(&self, merged: &mut ProxyOverrides) {
+        if let Some(enabled) = self.enabled {
+            merged.enabled = Some(enabled);
+        }
+        if let Some(url) = &self.url {
+            merged.url = Some(url.clone());
+        }
+        if let Some(username) = &self.username {
+            merged.username = Some(username.clone());
+        }
+        if let Some(password) = &self.password {
+            merged.password = Some(password.clone());
+        }
+        if let Some(no_proxy) = &self.no_proxy {
+            merged.no_proxy = Some(no_proxy.clone());
+        }
+    }
+
+    fn resolve(self) -> Option<ProxyConfig> {
+        if self.enabled == Some(false) {
+            return None;
+        }
+        let url = self.url.filter(|url| !url.trim().is_empty())?;
+        Some(ProxyConfig {
+            url,
+            username: self.username,
+            password: self.password,
+            no_proxy: self.no_proxy.unwrap_or_default(),
+        })
+    }
+}
+
+/// Per-region section of the settings file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegionSettings {
+    pub default_preset: Option<String>,
+    pub fetch: FetchOverrides,
+    pub proxy: ProxyOverrides,
+}
+
+/// User preferences loaded from `settings.json`, layered as built-in defaults, the global
+/// section, the matching `regions.<CODE>` section, and finally command-line flags.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub default_region: Option<String>,
+    pub default_preset: Option<String>,
+    pub fetch: FetchOverrides,
+    pub proxy: ProxyOverrides,
+    pub regions: HashMap<String, RegionSettings>,
+    #[serde(skip)]
+    cli: FetchOverrides,
+}
+
+impl Settings {
+    /// Read the settings file from the workspace root; a missing file yields the defaults.
+    pub fn load(workspace: &Workspace) -> Result<Self> {
+        let path = Self::path(workspace);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => {
+                return Err(AppError::message(format!(
+                    "Failed to read settings file {}: {}",
+                    path.display(),
+                    err
+                )))
+            }
+        };
+
+        let settings: Settings = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse settings file {}", path.display()))?;
+        Ok(settings)
+    }
+
+    pub fn path(workspace: &Workspace) -> PathBuf {
+        workspace.root().join(SETTINGS_FILE_NAME)
+    }
+
+    /// Layer command-line values above every section of the file.
+    pub fn with_cli_overrides(mut self, overrides: FetchOverrides) -> Self {
+        self.cli = overrides;
+        self
+    }
+
+    /// Fill the settings-driven fields of a region config built from its descriptor.
+    pub fn apply(&self, mut region: RegionConfig) -> RegionConfig {
+        region.fetch = self.fetch_tuning(&region.code);
+        region.proxy = self.proxy(&region.code);
+        region.default_preset = self.default_preset(&region.code).map(str::to_string);
+        region
+    }
+
+    /// Resolve the effective fetch tuning for `region_code`.
+    pub fn fetch_tuning(&self, region_code: &str) -> FetchTuning {
+        let mut tuning = FetchTuning::default();
+        self.fetch.apply(&mut tuning);
+        if let Some(region) = self.region(region_code) {
+            region.fetch.apply(&mut tuning);
+        }
+        self.cli.apply(&mut tuning);
+        tuning
+    }
+
+    /// Resolve the proxy for `region_code`; `None` means requests go out directly.
+    pub fn proxy(&self, region_code: &str) -> Option<ProxyConfig> {
+        let mut merged = ProxyOverrides::default();
+        self.proxy.apply(&mut merged);
+        if let Some(region) = self.region(region_code) {
+            region.proxy.apply(&mut merged);
+        }
+        merged.resolve()
+    }
+
+    /// Preset applied when `region_code` is opened, preferring the region section.
+    pub fn default_preset(&self, region_code: &str) -> Option<&str> {
+        self.region(region_code)
+            .and_then(|region| region.default_preset.as_deref())
+            .or(self.default_preset.as_deref())
+    }
+
+    fn region(&self, region_code: &str) -> Option<&RegionSettings> {
+        self.regions
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(region_code))
+            .map(|(_, region)| region)
+    }
+}
@@ -1,28 +1,126 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use log::warn;
+use serde::Serialize;
 
 use crate::error::{AppError, Result};
 
 use super::{
-    loader::RegionDescriptor, HistoryConfig, HistoryFieldIndices, HistoryResponse, InfoIndex,
-    JsonHistoryRowFormat, JsonPathSegment, ProviderConfig, RequestConfig, SnapshotConfig,
-    SnapshotResponse,
+    loader::{self, RegionDescriptor},
+    BodyContentType, HistoryConfig, HistoryFieldIndices, HistoryInterval, HistoryResponse,
+    HttpMethod, InfoIndex, InfoSource, JsonHistoryRowFormat, ProviderConfig, RateLimitConfig,
+    RegexResponseConfig, RequestBody, RequestConfig, RetryPolicy, SnapshotConfig, SnapshotResponse,
+    Workspace,
 };
 
-/// Validate a single region descriptor and surface descriptive errors.
-pub fn validate_region_descriptor(descriptor: &RegionDescriptor) -> Result<()> {
-    let mut issues = Vec::new();
+/// How serious a validation finding is. Only errors prevent a region from loading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single finding tied to the JSON field path it concerns (empty when it applies to the file).
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Outcome of loading and validating one `assets/configs/*.json` file in isolation.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub file: PathBuf,
+    pub region: Option<String>,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl FileReport {
+    pub fn error_count(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .count()
+    }
+}
+
+#[derive(Default)]
+struct Issues(Vec<ValidationIssue>);
+
+impl Issues {
+    fn error(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Error, path, message);
+    }
+
+    fn warning(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Warning, path, message);
+    }
+
+    fn push(&mut self, severity: Severity, path: impl Into<String>, message: impl Into<String>) {
+        self.0.push(ValidationIssue {
+            severity,
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+}
+
+/// Run every check against a loaded descriptor and return all findings.
+pub fn collect_region_issues(descriptor: &RegionDescriptor) -> Vec<ValidationIssue> {
+    let mut issues = Issues::default();
 
     validate_stock_list(descriptor, &mut issues);
     validate_thresholds(descriptor, &mut issues);
     validate_provider(descriptor, &mut issues);
+    validate_trading_sessions(descriptor, &mut issues);
 
-    if issues.is_empty() {
+    issues.0
+}
+
+/// Validate a single region descriptor and surface descriptive errors.
+pub fn validate_region_descriptor(descriptor: &RegionDescriptor) -> Result<()> {
+    let (errors, warnings): (Vec<_>, Vec<_>) = collect_region_issues(descriptor)
+        .into_iter()
+        .partition(|issue| issue.severity == Severity::Error);
+
+    for issue in &warnings {
+        warn!("region `{}` config: {}", descriptor.code, issue);
+    }
+
+    if errors.is_empty() {
         Ok(())
     } else {
         Err(AppError::message(format!(
             "region `{}` config invalid:\n  - {}",
             descriptor.code,
-            issues.join("\n  - ")
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n  - ")
         )))
     }
 }
@@ -52,182 +150,596 @@ pub fn validate_region_descriptors(descriptors: &[RegionDescriptor]) -> Result<(
     }
 }
 
-fn validate_stock_list(descriptor: &RegionDescriptor, issues: &mut Vec<String>) {
+/// Load every config in the workspace independently so one broken file does not hide the rest.
+pub fn validate_config_dir(workspace: &Workspace) -> Result<Vec<FileReport>> {
+    let configs_dir = workspace.configs_dir();
+    let mut files = fs::read_dir(configs_dir)
+        .map_err(|err| {
+            AppError::message(format!(
+                "failed to read region config directory {}: {}",
+                configs_dir.display(),
+                err
+            ))
+        })?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .collect::<Vec<_>>();
+    files.sort();
+
+    Ok(files
+        .into_iter()
+        .map(|file| validate_config_file(workspace, file))
+        .collect())
+}
+
+fn validate_config_file(workspace: &Workspace, file: PathBuf) -> FileReport {
+    let slug = file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .to_string();
+
+    match loader::build_region_descriptor(workspace, &slug) {
+        Ok(descriptor) => FileReport {
+            file,
+            region: Some(descriptor.code.clone()),
+            issues: collect_region_issues(&descriptor),
+        },
+        Err(err) => {
+            let (path, message) = match err {
+                AppError::InvalidConfig { path, message } => (path, message),
+                other => (String::new(), format!("{:#}", other)),
+            };
+            FileReport {
+                file,
+                region: None,
+                issues: vec![ValidationIssue {
+                    severity: Severity::Error,
+                    path,
+                    message,
+                }],
+            }
+        }
+    }
+}
+
+fn validate_stock_list(descriptor: &RegionDescriptor, issues: &mut Issues) {
     if descriptor.stock_codes.is_empty() {
-        issues.push("stock list CSV yielded no symbols".to_string());
+        issues.error("stock_list.file", "stock list CSV yielded no symbols");
     }
 }
 
-fn validate_thresholds(descriptor: &RegionDescriptor, issues: &mut Vec<String>) {
+fn validate_thresholds(descriptor: &RegionDescriptor, issues: &mut Issues) {
     for (metric, threshold) in &descriptor.thresholds {
         if threshold.lower > threshold.upper {
-            issues.push(format!(
-                "threshold `{metric}` has lower bound {} greater than upper bound {}",
-                threshold.lower, threshold.upper
-            ));
+            issues.error(
+                format!("thresholds.{metric}"),
+                format!(
+                    "lower bound {} is greater than upper bound {}",
+                    threshold.lower, threshold.upper
+                ),
+            );
+        }
+    }
+}
+
+fn validate_trading_sessions(descriptor: &RegionDescriptor, issues: &mut Issues) {
+    let Some(calendar) = &descriptor.trading_sessions else {
+        return;
+    };
+
+    if calendar.weekdays.is_empty() {
+        issues.error(
+            "trading_sessions.weekdays",
+            "must list at least one weekday",
+        );
+    }
+
+    if calendar.sessions.is_empty() {
+        issues.error(
+            "trading_sessions.sessions",
+            "must declare at least one session",
+        );
+    }
+
+    for (idx, session) in calendar.sessions.iter().enumerate() {
+        if session.start == session.end {
+            issues.error(
+                format!("trading_sessions.sessions[{idx}]"),
+                format!(
+                    "start and end are both {}; a session cannot be empty",
+                    session.start.format("%H:%M")
+                ),
+            );
         }
     }
 }
 
-fn validate_provider(descriptor: &RegionDescriptor, issues: &mut Vec<String>) {
+fn validate_provider(descriptor: &RegionDescriptor, issues: &mut Issues) {
     match &descriptor.provider {
         ProviderConfig::Tencent(provider) => {
             validate_snapshot_config(&provider.snapshot, issues);
-            validate_history_config(&provider.history, issues);
         }
         ProviderConfig::Stooq(provider) => {
             validate_snapshot_config(&provider.snapshot, issues);
-            validate_history_config(&provider.history, issues);
         }
     }
+
+    validate_history_intervals(&descriptor.provider, issues);
+
+    if let Some(rate_limit) = descriptor.provider.rate_limit() {
+        validate_rate_limit(rate_limit, issues);
+    }
 }
 
-fn validate_snapshot_config(snapshot: &SnapshotConfig, issues: &mut Vec<String>) {
-    validate_request(&snapshot.request, issues, "snapshot");
+fn validate_rate_limit(rate_limit: &RateLimitConfig, issues: &mut Issues) {
+    if !(rate_limit.requests_per_second.is_finite() && rate_limit.requests_per_second > 0.0) {
+        issues.error(
+            "provider.rate_limit.requests_per_second",
+            format!(
+                "must be a positive number, found {}",
+                rate_limit.requests_per_second
+            ),
+        );
+    }
+
+    if rate_limit.burst == 0 {
+        issues.error("provider.rate_limit.burst", "must be at least 1");
+    }
+}
+
+fn validate_snapshot_config(snapshot: &SnapshotConfig, issues: &mut Issues) {
+    validate_request(&snapshot.request, issues, "provider.snapshot");
     validate_snapshot_response(&snapshot.response, issues);
-    validate_info_indices(&snapshot.info_idxs, issues);
+    validate_info_indices(&snapshot.info_idxs, &snapshot.response, issues);
+    if let SnapshotResponse::Regex(regex) = &snapshot.response {
+        let mut labels = snapshot.info_idxs.keys().collect::<Vec<_>>();
+        labels.sort();
+        validate_capture_groups(
+            regex,
+            labels
+                .into_iter()
+                .filter_map(|label| match &snapshot.info_idxs[label].source {
+                    InfoSource::Position(index) => Some((label.as_str(), *index)),
+                    InfoSource::Path(_) => None,
+                }),
+            "provider.snapshot.info_indices",
+            issues,
+        );
+    }
+    validate_batching(snapshot, issues);
+}
+
+/// Flag indices that point past the last capture group of `regex`.
+fn validate_capture_groups<'a>(
+    regex: &RegexResponseConfig,
+    indices: impl Iterator<Item = (&'a str, usize)>,
+    context: &str,
+    issues: &mut Issues,
+) {
+    // Syntax errors are rejected while loading; only the group count matters here.
+    let Ok(compiled) = regex.compile("SYMBOL") else {
+        return;
+    };
+    let groups = compiled.captures_len();
+    for (label, index) in indices {
+        if index >= groups {
+            issues.error(
+                format!("{context}.{label}"),
+                format!(
+                    "capture group {index} does not exist; the pattern has {} group(s)",
+                    groups - 1
+                ),
+            );
+        }
+    }
+}
+
+fn validate_batching(snapshot: &SnapshotConfig, issues: &mut Issues) {
+    if snapshot.batch_size <= 1 {
+        return;
+    }
+
+    let body_references_symbols = snapshot
+        .request
+        .body
+        .as_ref()
+        .is_some_and(|body| body.template.to_string().contains("{symbols}"));
+    if !snapshot.request.url_template.contains("{symbols}") && !body_references_symbols {
+        issues.error(
+            "provider.snapshot.request.url_template",
+            "must reference `{symbols}` (or the request body must) when batch_size is greater than 1",
+        );
+    }
+
+    match &snapshot.response {
+        SnapshotResponse::Json(json) if !json.data_path.references_symbol() => {
+            issues.error(
+                "provider.snapshot.response.path",
+                "must reference `{symbol}` so batched payloads can be split per symbol",
+            );
+        }
+        SnapshotResponse::JsVars(vars) if !vars.variable.contains("{symbol}") => {
+            issues.error(
+                "provider.snapshot.response.variable",
+                "must reference `{symbol}` so batched payloads can be split per symbol",
+            );
+        }
+        SnapshotResponse::Regex(regex) if !regex.pattern.contains("{symbol}") => {
+            issues.error(
+                "provider.snapshot.response.pattern",
+                "must reference `{symbol}` so batched payloads can be split per symbol",
+            );
+        }
+        _ => {}
+    }
 }
 
-fn validate_request(request: &RequestConfig, issues: &mut Vec<String>, context: &str) {
+fn validate_request(request: &RequestConfig, issues: &mut Issues, context: &str) {
     if request.url_template.trim().is_empty() {
-        issues.push(format!("{context}.request.url_template must not be empty"));
+        issues.error(
+            format!("{context}.request.url_template"),
+            "must not be empty",
+        );
+    }
+
+    match (request.method, &request.body) {
+        (HttpMethod::Get, Some(_)) => issues.error(
+            format!("{context}.request.body"),
+            "is only sent with POST or PUT requests",
+        ),
+        (HttpMethod::Post | HttpMethod::Put, None) => issues.warning(
+            format!("{context}.request.method"),
+            format!("{} request has no body", request.method.label()),
+        ),
+        _ => {}
     }
 
-    match request.method {
-        super::HttpMethod::Get => {}
+    if let Some(body) = &request.body {
+        validate_request_body(request, body, issues, &format!("{context}.request.body"));
     }
+
+    validate_retry(&request.retry, issues, &format!("{context}.request.retry"));
 }
 
-fn validate_snapshot_response(response: &SnapshotResponse, issues: &mut Vec<String>) {
+fn validate_request_body(
+    request: &RequestConfig,
+    body: &RequestBody,
+    issues: &mut Issues,
+    context: &str,
+) {
+    match body.content_type {
+        BodyContentType::Json => {
+            if body.template.is_null() {
+                issues.error(format!("{context}.template"), "must not be null");
+            }
+        }
+        BodyContentType::Form => match body.template.as_object() {
+            Some(fields) => {
+                for (key, value) in fields {
+                    if value.is_object() || value.is_array() || value.is_null() {
+                        issues.error(
+                            format!("{context}.template.{key}"),
+                            "form fields must be strings, numbers, or booleans",
+                        );
+                    }
+                }
+            }
+            None => issues.error(
+                format!("{context}.template"),
+                "must be an object of field names to values for form bodies",
+            ),
+        },
+    }
+
+    if let Some((name, value)) = request
+        .headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+    {
+        let mime = body.content_type.mime();
+        if !value.to_ascii_lowercase().starts_with(mime) {
+            issues.warning(
+                format!("{context}.content_type"),
+                format!(
+                    "`{}` body overridden by header {}: {}",
+                    body.content_type.label(),
+                    name,
+                    value
+                ),
+            );
+        }
+    }
+}
+
+fn validate_retry(retry: &RetryPolicy, issues: &mut Issues, context: &str) {
+    if retry.max_attempts == 0 {
+        issues.error(format!("{context}.max_attempts"), "must be at least 1");
+    }
+
+    if !(0.0..=1.0).contains(&retry.jitter) {
+        issues.error(
+            format!("{context}.jitter"),
+            format!("must be between 0.0 and 1.0, found {}", retry.jitter),
+        );
+    }
+
+    if retry.base_delay > retry.max_delay {
+        issues.error(
+            format!("{context}.base_delay_ms"),
+            format!(
+                "{} ms exceeds max_delay_ms {} ms",
+                retry.base_delay.as_millis(),
+                retry.max_delay.as_millis()
+            ),
+        );
+    }
+
+    for (idx, status) in retry.retry_statuses.iter().enumerate() {
+        if !(100..=599).contains(status) {
+            issues.error(
+                format!("{context}.retry_statuses[{idx}]"),
+                format!("{status} is not an HTTP status code"),
+            );
+        }
+    }
+}
+
+fn validate_snapshot_response(response: &SnapshotResponse, issues: &mut Issues) {
     match response {
         SnapshotResponse::Json(json) => {
             if json.data_path.is_empty() {
-                issues.push("snapshot.response.path must contain at least one segment".to_string());
+                issues.error(
+                    "provider.snapshot.response.path",
+                    "must contain at least one segment",
+                );
             }
 
-            if !json.data_path.iter().any(matches_symbol_segment) {
-                issues.push(
-                    "snapshot.response.path should reference `{symbol}` for code substitution"
-                        .to_string(),
+            if !json.data_path.references_symbol() {
+                issues.error(
+                    "provider.snapshot.response.path",
+                    "must reference `{symbol}` for code substitution",
                 );
             }
         }
         SnapshotResponse::Delimited(delimited) => {
             if delimited.delimiter == '\0' {
-                issues.push(
-                    "snapshot.response.delimited delimiter must be a visible character".to_string(),
+                issues.error(
+                    "provider.snapshot.response.delimiter",
+                    "must be a visible character",
                 );
             }
         }
+        SnapshotResponse::JsVars(vars) => {
+            if vars.variable.trim().is_empty() {
+                issues.error("provider.snapshot.response.variable", "must not be empty");
+            } else if !vars.variable.contains("{symbol}") {
+                issues.error(
+                    "provider.snapshot.response.variable",
+                    "must reference `{symbol}` for code substitution",
+                );
+            }
+            if vars.delimiter == '\0' {
+                issues.error(
+                    "provider.snapshot.response.delimiter",
+                    "must be a visible character",
+                );
+            }
+        }
+        SnapshotResponse::Regex(regex) => {
+            if regex.pattern.trim().is_empty() {
+                issues.error("provider.snapshot.response.pattern", "must not be empty");
+            }
+        }
     }
 }
 
-fn matches_symbol_segment(segment: &JsonPathSegment) -> bool {
-    matches!(segment, JsonPathSegment::StockCode)
-}
-
-fn validate_info_indices(info_idxs: &HashMap<String, InfoIndex>, issues: &mut Vec<String>) {
+fn validate_info_indices(
+    info_idxs: &HashMap<String, InfoIndex>,
+    response: &SnapshotResponse,
+    issues: &mut Issues,
+) {
     if info_idxs.is_empty() {
-        issues.push("snapshot.info_indices must define at least one mapping".to_string());
+        issues.error(
+            "provider.snapshot.info_indices",
+            "must define at least one mapping",
+        );
         return;
     }
 
-    let mut seen = HashMap::<usize, String>::new();
-    let mut duplicates = Vec::new();
+    let mut labels = info_idxs.keys().collect::<Vec<_>>();
+    labels.sort();
 
-    for (label, idx) in info_idxs {
-        if let Some(existing) = seen.insert(idx.index, label.clone()) {
-            duplicates.push(format!(
-                "index {} assigned to both `{existing}` and `{label}`",
-                idx.index
-            ));
+    let mut seen = HashMap::<usize, &String>::new();
+    for label in labels {
+        match &info_idxs[label].source {
+            InfoSource::Position(index) => {
+                if let Some(existing) = seen.insert(*index, label) {
+                    issues.error(
+                        format!("provider.snapshot.info_indices.{label}"),
+                        format!("index {index} is already assigned to `{existing}`"),
+                    );
+                }
+            }
+            InfoSource::Path(path) => {
+                if !matches!(response, SnapshotResponse::Json(_)) {
+                    issues.error(
+                        format!("provider.snapshot.info_indices.{label}"),
+                        "keys and paths need a `json_path` response; use a position instead",
+                    );
+                } else if path.is_empty() {
+                    issues.error(
+                        format!("provider.snapshot.info_indices.{label}"),
+                        "must contain at least one segment",
+                    );
+                }
+            }
         }
     }
+}
 
-    if !duplicates.is_empty() {
-        issues.push(format!(
-            "snapshot.info_indices contains duplicate positions: {}",
-            duplicates.join(", ")
-        ));
+fn validate_history_intervals(provider: &ProviderConfig, issues: &mut Issues) {
+    let mut seen = HashMap::<HistoryInterval, String>::new();
+    for (idx, history) in provider.histories().enumerate() {
+        let context = match idx {
+            0 => "provider.history".to_string(),
+            n => format!("provider.history_intervals[{}]", n - 1),
+        };
+        if let Some(existing) = seen.insert(history.interval, context.clone()) {
+            issues.error(
+                format!("{context}.interval"),
+                format!(
+                    "interval `{}` is already declared by `{existing}`",
+                    history.interval.label()
+                ),
+            );
+        }
+        validate_history_config(history, &context, issues);
     }
 }
 
-fn validate_history_config(history: &HistoryConfig, issues: &mut Vec<String>) {
-    validate_request(&history.request, issues, "history");
+fn validate_history_config(history: &HistoryConfig, context: &str, issues: &mut Issues) {
+    validate_request(&history.request, issues, context);
 
     if let Some(limit) = history.limit {
         if limit == 0 {
-            issues.push("history.limit must be greater than zero when provided".to_string());
+            issues.error(
+                format!("{context}.limit"),
+                "must be greater than zero when provided",
+            );
         }
     }
 
+    let date_format = match &history.response {
+        HistoryResponse::JsonRows(json) => &json.date_format,
+        HistoryResponse::CsvRows(csv) => &csv.date_format,
+        HistoryResponse::RegexRows(regex) => &regex.date_format,
+    };
+    if history.interval.is_intraday() && !date_format.contains("%H") && !date_format.contains("%T")
+    {
+        issues.warning(
+            format!("{context}.response.date_format"),
+            format!(
+                "`{}` candles need a time of day; the format has no `%H`",
+                history.interval.label()
+            ),
+        );
+    }
+
     match &history.response {
         HistoryResponse::JsonRows(json) => {
             if json.data_path.is_empty() {
-                issues.push(
-                    "history.response.json_rows.path must contain at least one segment".to_string(),
+                issues.error(
+                    format!("{context}.response.path"),
+                    "must contain at least one segment",
                 );
             }
 
-            if !json.data_path.iter().any(matches_symbol_segment) {
-                issues.push(
-                    "history.response.json_rows.path should reference `{symbol}` for code substitution"
-                        .to_string(),
+            if !json.data_path.references_symbol() {
+                issues.error(
+                    format!("{context}.response.path"),
+                    "must reference `{symbol}` for code substitution",
                 );
             }
 
             if json.date_format.trim().is_empty() {
-                issues.push("history.response.json_rows.date_format must not be empty".to_string());
+                issues.error(
+                    format!("{context}.response.date_format"),
+                    "must not be empty",
+                );
             }
 
             match &json.row_format {
                 JsonHistoryRowFormat::Array(indices) => {
-                    validate_history_indices(indices, "history.response.json_rows.columns", issues);
+                    validate_history_indices(
+                        indices,
+                        &format!("{context}.response.columns"),
+                        issues,
+                    );
                 }
                 JsonHistoryRowFormat::StringDelimited { delimiter, indices } => {
                     if *delimiter == '\0' {
-                        issues.push(
-                            "history.response.json_rows.row.delimiter must be a visible character"
-                                .to_string(),
+                        issues.error(
+                            format!("{context}.response.row.delimiter"),
+                            "must be a visible character",
                         );
                     }
-                    validate_history_indices(indices, "history.response.json_rows.columns", issues);
+                    validate_history_indices(
+                        indices,
+                        &format!("{context}.response.columns"),
+                        issues,
+                    );
                 }
             }
         }
         HistoryResponse::CsvRows(csv) => {
             if csv.date_format.trim().is_empty() {
-                issues.push("history.response.csv_rows.date_format must not be empty".to_string());
+                issues.error(
+                    format!("{context}.response.date_format"),
+                    "must not be empty",
+                );
             }
             if csv.delimiter == '\0' {
-                issues.push(
-                    "history.response.csv_rows.delimiter must be a visible character".to_string(),
+                issues.error(
+                    format!("{context}.response.delimiter"),
+                    "must be a visible character",
+                );
+            }
+            validate_history_indices(&csv.indices, &format!("{context}.response.columns"), issues);
+        }
+        HistoryResponse::RegexRows(regex) => {
+            if regex.date_format.trim().is_empty() {
+                issues.error(
+                    format!("{context}.response.date_format"),
+                    "must not be empty",
                 );
             }
-            validate_history_indices(&csv.indices, "history.response.csv_rows.columns", issues);
+            let columns = format!("{context}.response.columns");
+            validate_history_indices(&regex.indices, &columns, issues);
+            let indices = &regex.indices;
+            let fields = [
+                ("date", Some(indices.date)),
+                ("open", Some(indices.open)),
+                ("high", Some(indices.high)),
+                ("low", Some(indices.low)),
+                ("close", Some(indices.close)),
+                ("volume", indices.volume),
+                ("amount", indices.amount),
+            ];
+            validate_capture_groups(
+                &regex.pattern,
+                fields
+                    .into_iter()
+                    .filter_map(|(label, index)| index.map(|index| (label, index))),
+                &columns,
+                issues,
+            );
         }
     }
 }
 
-fn validate_history_indices(
-    indices: &HistoryFieldIndices,
-    context: &str,
-    issues: &mut Vec<String>,
-) {
+fn validate_history_indices(indices: &HistoryFieldIndices, context: &str, issues: &mut Issues) {
     let mut seen = HashMap::<usize, &str>::new();
     let fields = [
-        ("date", indices.date),
-        ("open", indices.open),
-        ("high", indices.high),
-        ("low", indices.low),
-        ("close", indices.close),
+        ("date", Some(indices.date)),
+        ("open", Some(indices.open)),
+        ("high", Some(indices.high)),
+        ("low", Some(indices.low)),
+        ("close", Some(indices.close)),
+        ("volume", indices.volume),
+        ("amount", indices.amount),
     ];
 
-    for (label, idx) in fields {
+    for (label, idx) in fields
+        .into_iter()
+        .filter_map(|(label, idx)| idx.map(|idx| (label, idx)))
+    {
         if let Some(existing) = seen.insert(idx, label) {
-            issues.push(format!(
-                "{context} maps index {idx} to both `{existing}` and `{label}`"
-            ));
+            issues.error(
+                format!("{context}.{label}"),
+                format!("index {idx} is already assigned to `{existing}`"),
+            );
         }
     }
 }
@@ -0,0 +1,94 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Context, Result};
+
+/// Environment variable that points at the workspace root when `--root` is not given.
+pub const HOME_ENV: &str = "STOCK_CLI_HOME";
+
+const APP_DIR_NAME: &str = "stock-cli";
+
+/// Directories the application reads descriptors from and resolves relative paths against.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    root: PathBuf,
+    configs_dir: PathBuf,
+}
+
+impl Workspace {
+    /// Use `root` with the default `assets/configs` layout underneath it.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        let configs_dir = root.join("assets").join("configs");
+        Self { root, configs_dir }
+    }
+
+    /// Resolve the workspace from explicit overrides, falling back to `STOCK_CLI_HOME`, the
+    /// current directory when it holds `assets/configs`, and finally the XDG data directory.
+    pub fn resolve(root: Option<PathBuf>, configs_dir: Option<PathBuf>) -> Result<Self> {
+        let cwd = env::current_dir().context("Failed to determine current directory")?;
+        let root = match root {
+            Some(root) => absolutize(&cwd, root),
+            None => default_root(&cwd),
+        };
+
+        let mut workspace = Self::new(root);
+        if let Some(dir) = configs_dir {
+            workspace.configs_dir = absolutize(&cwd, dir);
+        }
+        Ok(workspace)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn configs_dir(&self) -> &Path {
+        &self.configs_dir
+    }
+
+    /// Resolve a path from a region config: absolute paths are kept, relative ones join the root.
+    pub fn resolve_path(&self, value: impl AsRef<Path>) -> PathBuf {
+        let path = value.as_ref();
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.root.join(path)
+        }
+    }
+}
+
+fn default_root(cwd: &Path) -> PathBuf {
+    if let Some(home) = env::var_os(HOME_ENV).filter(|value| !value.is_empty()) {
+        return absolutize(cwd, PathBuf::from(home));
+    }
+
+    if cwd.join("assets").join("configs").is_dir() {
+        return cwd.to_path_buf();
+    }
+
+    xdg_data_home()
+        .map(|dir| dir.join(APP_DIR_NAME))
+        .unwrap_or_else(|| cwd.to_path_buf())
+}
+
+fn xdg_data_home() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_DATA_HOME").filter(|value| !value.is_empty()) {
+        let dir = PathBuf::from(dir);
+        if dir.is_absolute() {
+            return Some(dir);
+        }
+    }
+
+    env::var_os("HOME")
+        .filter(|value| !value.is_empty())
+        .map(|home| PathBuf::from(home).join(".local").join("share"))
+}
+
+fn absolutize(cwd: &Path, path: PathBuf) -> PathBuf {
+    if path.is_absolute() {
+        path
+    } else {
+        cwd.join(path)
+    }
+}
@@ -28,6 +28,8 @@ pub enum AppError {
     Cancelled,
     #[error("{0}")]
     Message(String),
+    #[error("{path}: {message}")]
+    InvalidConfig { path: String, message: String },
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -36,4 +38,12 @@ impl AppError {
     pub fn message<T: Into<String>>(msg: T) -> Self {
         AppError::Message(msg.into())
     }
+
+    /// Region config problem tied to the JSON field path that caused it.
+    pub fn invalid_config<P: Into<String>, T: Into<String>>(path: P, msg: T) -> Self {
+        AppError::InvalidConfig {
+            path: path.into(),
+            message: msg.into(),
+        }
+    }
 }
@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use chrono::Datelike;
+
+use crate::fetch::Candle;
+
+/// Calendar period that finer candles are rolled up into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregatePeriod {
+    /// ISO week, Monday to Sunday.
+    Week,
+    Month,
+}
+
+impl AggregatePeriod {
+    pub const ALL: [AggregatePeriod; 2] = [AggregatePeriod::Week, AggregatePeriod::Month];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AggregatePeriod::Week => "week",
+            AggregatePeriod::Month => "month",
+        }
+    }
+
+    pub fn from_label(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|period| period.label() == value)
+    }
+
+    /// Longest calendar span of one period.
+    pub fn max_span(self) -> Duration {
+        const DAY: u64 = 24 * 60 * 60;
+        Duration::from_secs(match self {
+            AggregatePeriod::Week => 7 * DAY,
+            AggregatePeriod::Month => 31 * DAY,
+        })
+    }
+
+    fn bucket(self, candle: &Candle) -> (i32, u32) {
+        let date = candle.timestamp.date_naive();
+        match self {
+            AggregatePeriod::Week => {
+                let week = date.iso_week();
+                (week.year(), week.week())
+            }
+            AggregatePeriod::Month => (date.year(), date.month()),
+        }
+    }
+}
+
+/// Roll candles up into one bar per calendar period.
+///
+/// Each bar opens at the first candle's open and closes at the last candle's close, spans their
+/// highest high and lowest low, sums volume and amount, and is stamped with its first candle's
+/// timestamp. Input order does not matter.
+pub fn aggregate_candles(candles: &[Candle], period: AggregatePeriod) -> Vec<Candle> {
+    let mut sorted = candles.to_vec();
+    sorted.sort_by_key(|candle| candle.timestamp);
+
+    let mut bars: Vec<Candle> = Vec::new();
+    let mut current_bucket = None;
+    for candle in sorted {
+        let bucket = period.bucket(&candle);
+        match bars.last_mut() {
+            Some(bar) if current_bucket == Some(bucket) => {
+                bar.high = bar.high.max(candle.high);
+                bar.low = bar.low.min(candle.low);
+                bar.close = candle.close;
+                bar.volume = add_optional(bar.volume, candle.volume);
+                bar.amount = add_optional(bar.amount, candle.amount);
+            }
+            _ => {
+                current_bucket = Some(bucket);
+                bars.push(candle);
+            }
+        }
+    }
+    bars
+}
+
+fn add_optional(total: Option<f64>, value: Option<f64>) -> Option<f64> {
+    match (total, value) {
+        (Some(total), Some(value)) => Some(total + value),
+        (total, value) => total.or(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, NaiveDate, TimeZone};
+
+    use super::*;
+
+    fn daily(date: &str, close: f64) -> Candle {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        Candle {
+            timestamp: Local
+                .from_local_datetime(&date.and_hms_opt(15, 0, 0).unwrap())
+                .unwrap(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: None,
+            amount: None,
+        }
+    }
+
+    fn bar_dates(bars: &[Candle]) -> Vec<String> {
+        bars.iter()
+            .map(|bar| bar.timestamp.format("%Y-%m-%d").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn weeks_follow_iso_weeks_across_the_new_year() {
+        // Mon 2024-12-30 through Fri 2025-01-03 is ISO week 2025-W01.
+        let candles: Vec<Candle> = ["2024-12-27", "2024-12-30", "2025-01-03", "2025-01-06"]
+            .into_iter()
+            .map(|date| daily(date, 1.0))
+            .collect();
+
+        let weeks = aggregate_candles(&candles, AggregatePeriod::Week);
+        assert_eq!(
+            bar_dates(&weeks),
+            ["2024-12-27", "2024-12-30", "2025-01-06"]
+        );
+    }
+
+    #[test]
+    fn months_split_on_the_calendar_month() {
+        let candles: Vec<Candle> = ["2024-12-30", "2024-12-31", "2025-01-02", "2025-01-31"]
+            .into_iter()
+            .map(|date| daily(date, 1.0))
+            .collect();
+
+        let months = aggregate_candles(&candles, AggregatePeriod::Month);
+        assert_eq!(bar_dates(&months), ["2024-12-30", "2025-01-02"]);
+    }
+
+    #[test]
+    fn bars_span_their_candles_in_time_order() {
+        let mut monday = daily("2025-01-06", 11.0);
+        (monday.open, monday.high, monday.low) = (10.0, 12.0, 9.0);
+        monday.volume = Some(100.0);
+        let mut tuesday = daily("2025-01-07", 10.0);
+        tuesday.low = 8.0;
+        let mut wednesday = daily("2025-01-08", 13.0);
+        wednesday.high = 14.0;
+        wednesday.volume = Some(200.0);
+        wednesday.amount = Some(2600.0);
+
+        let bars = aggregate_candles(&[wednesday, monday, tuesday], AggregatePeriod::Week);
+        assert_eq!(bars.len(), 1);
+        let week = &bars[0];
+        assert_eq!(bar_dates(&bars), ["2025-01-06"]);
+        assert_eq!(
+            (week.open, week.high, week.low, week.close),
+            (10.0, 14.0, 8.0, 13.0)
+        );
+        // Days without volume or amount do not blank out the totals.
+        assert_eq!(week.volume, Some(300.0));
+        assert_eq!(week.amount, Some(2600.0));
+    }
+}
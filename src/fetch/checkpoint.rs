@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, Context};
+use crate::fetch::{FetchResult, StockData};
+
+const STARTED_AT_FMT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// First line of the journal, identifying which fetch the rows belong to.
+#[derive(Debug, Serialize, Deserialize)]
+struct CheckpointHeader {
+    region: String,
+    started_at: String,
+}
+
+/// Rows already fetched by an interrupted or partially failed snapshot.
+#[derive(Debug, Clone)]
+pub struct CheckpointState {
+    pub started_at: String,
+    pub rows: Vec<StockData>,
+}
+
+impl CheckpointState {
+    /// Time since the checkpointed fetch started; `None` when the header time is unreadable.
+    pub fn age(&self) -> Option<Duration> {
+        let started = NaiveDateTime::parse_from_str(&self.started_at, STARTED_AT_FMT).ok()?;
+        let started = Local.from_local_datetime(&started).earliest()?;
+        (Local::now() - started).to_std().ok()
+    }
+}
+
+/// Append-only journal (JSON lines) of the rows a snapshot fetch has completed, so a later run
+/// only needs the symbols that failed or were never reached.
+#[derive(Debug)]
+pub struct FetchCheckpoint {
+    path: PathBuf,
+    region: String,
+    writer: Mutex<Option<File>>,
+}
+
+impl FetchCheckpoint {
+    pub fn new(path: impl Into<PathBuf>, region: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            region: region.into(),
+            writer: Mutex::new(None),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the journal; `None` when there is none for this region.
+    pub fn load(&self) -> FetchResult<Option<CheckpointState>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(AppError::message(format!(
+                    "Failed to read fetch checkpoint {}: {}",
+                    self.path.display(),
+                    err
+                )))
+            }
+        };
+
+        let mut lines = contents.lines();
+        let header: CheckpointHeader = match lines.next().map(serde_json::from_str) {
+            Some(Ok(header)) => header,
+            _ => {
+                warn!(
+                    "Ignoring unreadable fetch checkpoint {}",
+                    self.path.display()
+                );
+                return Ok(None);
+            }
+        };
+        if !header.region.eq_ignore_ascii_case(&self.region) {
+            return Ok(None);
+        }
+
+        // Later rows win so a symbol fetched again replaces its earlier entry.
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        let mut rows: Vec<StockData> = Vec::new();
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            // A torn final line from an interrupted write is simply refetched.
+            let Ok(row) = serde_json::from_str::<StockData>(line) else {
+                continue;
+            };
+            match positions.get(&row.stock_code) {
+                Some(&idx) => rows[idx] = row,
+                None => {
+                    positions.insert(row.stock_code.clone(), rows.len());
+                    rows.push(row);
+                }
+            }
+        }
+
+        Ok(Some(CheckpointState {
+            started_at: header.started_at,
+            rows,
+        }))
+    }
+
+    /// Begin a new journal, discarding rows from any previous fetch.
+    pub fn start(&self) -> FetchResult<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create checkpoint directory {}", parent.display())
+            })?;
+        }
+        let mut file = File::create(&self.path).with_context(|| {
+            format!("Failed to create fetch checkpoint {}", self.path.display())
+        })?;
+        let header = CheckpointHeader {
+            region: self.region.clone(),
+            started_at: Local::now().format(STARTED_AT_FMT).to_string(),
+        };
+        writeln!(file, "{}", serde_json::to_string(&header)?)?;
+        *self.lock_writer() = Some(file);
+        Ok(())
+    }
+
+    /// Keep appending to the existing journal.
+    pub fn resume(&self) -> FetchResult<()> {
+        let file = OpenOptions::new()
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open fetch checkpoint {}", self.path.display()))?;
+        *self.lock_writer() = Some(file);
+        Ok(())
+    }
+
+    /// Append completed rows; a no-op until `start` or `resume` has been called.
+    pub fn record<'a>(&self, rows: impl IntoIterator<Item = &'a StockData>) -> FetchResult<()> {
+        let mut guard = self.lock_writer();
+        let Some(file) = guard.as_mut() else {
+            return Ok(());
+        };
+
+        let mut writer = BufWriter::new(file);
+        for row in rows {
+            serde_json::to_writer(&mut writer, row)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Remove the journal once a fetch has completed every symbol.
+    pub fn clear(&self) -> FetchResult<()> {
+        self.lock_writer().take();
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(AppError::message(format!(
+                "Failed to remove fetch checkpoint {}: {}",
+                self.path.display(),
+                err
+            ))),
+        }
+    }
+
+    fn lock_writer(&self) -> std::sync::MutexGuard<'_, Option<File>> {
+        self.writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use reqwest::{Client, NoProxy, Proxy, Response};
+
+use crate::config::{ProxyConfig, ResponseEncoding};
+use crate::error::{AppError, Context};
+use crate::fetch::request::expand_env_vars;
+use crate::fetch::FetchResult;
+
+/// Time allowed to establish a connection; per-request timeouts come from the region settings.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long idle pooled connections are kept for reuse.
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Async client for requests routed through `proxy`, or sent directly when it is `None`.
+///
+/// Clients are built once per distinct proxy and shared process-wide, so snapshot and history
+/// requests of regions behind the same proxy share one connection pool.
+pub fn client_for(proxy: Option<&ProxyConfig>) -> FetchResult<Client> {
+    static CLIENTS: OnceLock<Mutex<HashMap<Option<ProxyConfig>, Client>>> = OnceLock::new();
+    let clients = CLIENTS.get_or_init(|| Mutex::new(HashMap::new()));
+    let lock = || {
+        clients
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    };
+    let key = proxy.cloned();
+
+    if let Some(client) = lock().get(&key) {
+        return Ok(client.clone());
+    }
+
+    // Build outside the lock; if another task won the race, its client is the shared one.
+    let client = build_client(proxy)?;
+    Ok(lock().entry(key).or_insert(client).clone())
+}
+
+fn build_client(proxy: Option<&ProxyConfig>) -> FetchResult<Client> {
+    let mut builder = Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT);
+    if let Some(config) = proxy {
+        builder = builder.proxy(build_proxy(config)?);
+    }
+    builder
+        .build()
+        .context("Failed to configure HTTP client")
+        .map_err(AppError::from)
+}
+
+fn build_proxy(config: &ProxyConfig) -> FetchResult<Proxy> {
+    let url = expand_env_vars(&config.url)?;
+    let mut proxy =
+        Proxy::all(url.as_str()).with_context(|| format!("Invalid proxy URL {}", config.url))?;
+
+    if let Some(username) = &config.username {
+        let username = expand_env_vars(username)?;
+        let password = match &config.password {
+            Some(password) => expand_env_vars(password)?,
+            None => String::new(),
+        };
+        proxy = proxy.basic_auth(&username, &password);
+    }
+
+    if !config.no_proxy.is_empty() {
+        let mut hosts = Vec::with_capacity(config.no_proxy.len());
+        for host in &config.no_proxy {
+            hosts.push(expand_env_vars(host)?);
+        }
+        proxy = proxy.no_proxy(NoProxy::from_string(&hosts.join(",")));
+    }
+    Ok(proxy)
+}
+
+/// Read a response body, decoding it with `encoding` when the provider declares one and with
+/// the `Content-Type` charset otherwise.
+pub async fn read_text(
+    response: Response,
+    encoding: Option<ResponseEncoding>,
+) -> reqwest::Result<String> {
+    match encoding {
+        Some(encoding) => Ok(encoding.decode(&response.bytes().await?)),
+        None => response.text().await,
+    }
+}
@@ -1,36 +1,168 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
 
-use chrono::{NaiveDate, ParseResult};
+use chrono::{NaiveDate, NaiveDateTime};
 use serde_json::Value;
 
-use crate::config::{JsonHistoryRowFormat, JsonPathSegment};
+use crate::config::{
+    JsonHistoryRowFormat, JsonPath, JsonPathSegment, PredicateValue, ValueTransform,
+};
 use crate::error::AppError;
 
 use super::FetchResult;
 
+/// Resolve `path` against `root`, trying each alternative in order. `{symbol}` segments and
+/// predicates match the raw code or, when given, the transformed code.
 pub fn walk_json_path<'a>(
     root: &'a Value,
+    path: &JsonPath,
+    raw_code: &str,
+    transformed_code: Option<&str>,
+) -> FetchResult<&'a Value> {
+    let mut last_error = None;
+    for segments in &path.alternatives {
+        match walk_segments(root, segments, raw_code, transformed_code) {
+            Ok(node) => return Ok(node),
+            Err(err) => last_error = Some(err),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| AppError::message("JSON path has no segments")))
+}
+
+fn walk_segments<'a>(
+    cursor: &'a Value,
     path: &[JsonPathSegment],
     raw_code: &str,
     transformed_code: Option<&str>,
 ) -> FetchResult<&'a Value> {
-    let mut cursor = root;
-    for segment in path {
-        cursor = match segment {
-            JsonPathSegment::Key(key) => cursor.get(key).ok_or_else(|| {
-                AppError::message(format!("Missing key `{key}` while navigating JSON path"))
+    let Some((segment, rest)) = path.split_first() else {
+        return Ok(cursor);
+    };
+    let next = match segment {
+        JsonPathSegment::Key(key) => cursor.get(key).ok_or_else(|| {
+            AppError::message(format!("Missing key `{key}` while navigating JSON path"))
+        })?,
+        JsonPathSegment::StockCode => cursor
+            .get(raw_code)
+            .or_else(|| transformed_code.and_then(|code| cursor.get(code)))
+            .ok_or_else(|| {
+                AppError::message(format!(
+                    "Missing entry for stock `{raw_code}` while navigating JSON path"
+                ))
             })?,
-            JsonPathSegment::StockCode => cursor
-                .get(raw_code)
-                .or_else(|| transformed_code.and_then(|code| cursor.get(code)))
+        JsonPathSegment::Index(index) => {
+            let items = expect_array(cursor, || format!("[{index}]"))?;
+            let position = if *index < 0 {
+                items.len().checked_sub(index.unsigned_abs())
+            } else {
+                Some(index.unsigned_abs())
+            };
+            position.and_then(|idx| items.get(idx)).ok_or_else(|| {
+                AppError::message(format!(
+                    "Index [{index}] is out of bounds for an array of {} while navigating JSON path",
+                    items.len()
+                ))
+            })?
+        }
+        JsonPathSegment::Wildcard => {
+            let children: Box<dyn Iterator<Item = &Value>> = match cursor {
+                Value::Array(items) => Box::new(items.iter()),
+                Value::Object(fields) => Box::new(fields.values()),
+                _ => Box::new(std::iter::empty()),
+            };
+            let mut last_error = None;
+            for child in children {
+                match walk_segments(child, rest, raw_code, transformed_code) {
+                    Ok(node) => return Ok(node),
+                    Err(err) => last_error = Some(err),
+                }
+            }
+            return Err(last_error.unwrap_or_else(|| {
+                AppError::message("Wildcard `*` matched nothing while navigating JSON path")
+            }));
+        }
+        JsonPathSegment::Predicate { field, value } => {
+            let items = expect_array(cursor, || format!("[?{field}=...]"))?;
+            let matches = |candidate: &str| match value {
+                PredicateValue::Literal(expected) => candidate == expected,
+                PredicateValue::StockCode => {
+                    candidate == raw_code || transformed_code == Some(candidate)
+                }
+            };
+            items
+                .iter()
+                .find(|item| {
+                    item.get(field)
+                        .is_some_and(|candidate| matches(&value_to_string(candidate)))
+                })
                 .ok_or_else(|| {
+                    let expected = match value {
+                        PredicateValue::Literal(expected) => expected.as_str(),
+                        PredicateValue::StockCode => raw_code,
+                    };
                     AppError::message(format!(
-                        "Missing entry for stock `{raw_code}` while navigating JSON path"
+                        "No element with `{field}` = `{expected}` while navigating JSON path"
                     ))
-                })?,
+                })?
+        }
+    };
+    walk_segments(next, rest, raw_code, transformed_code)
+}
+
+fn expect_array(cursor: &Value, segment: impl FnOnce() -> String) -> FetchResult<&Vec<Value>> {
+    cursor.as_array().ok_or_else(|| {
+        AppError::message(format!(
+            "Expected an array for `{}` while navigating JSON path",
+            segment()
+        ))
+    })
+}
+
+/// Collect the `name="value"` assignments of a script-style payload such as
+/// `v_sh600000="1~...";` or `var hq_str_sh600000="...";`, keyed by variable name.
+pub fn parse_js_vars(text: &str) -> HashMap<&str, &str> {
+    let is_name_char = |ch: char| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '$' | '.');
+    let mut vars = HashMap::new();
+    let mut rest = text;
+    while let Some(assign) = rest.find("=\"") {
+        let head = &rest[..assign];
+        let name_start = head
+            .char_indices()
+            .rev()
+            .find(|(_, ch)| !is_name_char(*ch))
+            .map(|(idx, ch)| idx + ch.len_utf8())
+            .unwrap_or(0);
+        let value_start = assign + 2;
+        let Some(value_len) = rest[value_start..].find('"') else {
+            break;
         };
+        let name = &head[name_start..];
+        if !name.is_empty() {
+            vars.insert(name, &rest[value_start..value_start + value_len]);
+        }
+        rest = &rest[value_start + value_len + 1..];
+    }
+    vars
+}
+
+/// Body of a JSONP response such as `callback({...});`; unwrapped JSON is returned as is.
+pub fn strip_jsonp(text: &str) -> &str {
+    let trimmed = text.trim();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        return trimmed;
+    }
+    match (trimmed.find('('), trimmed.rfind(')')) {
+        (Some(open), Some(close)) if open < close => trimmed[open + 1..close].trim(),
+        _ => trimmed,
     }
-    Ok(cursor)
+}
+
+/// Text of every capture group, by group number; groups that did not participate are empty.
+pub fn capture_values(captures: &regex::Captures<'_>) -> Vec<String> {
+    captures
+        .iter()
+        .map(|group| group.map_or_else(String::new, |group| group.as_str().to_string()))
+        .collect()
 }
 
 pub fn value_to_string(value: &Value) -> String {
@@ -71,10 +203,250 @@ pub fn split_csv_line<'a>(line: &'a str, delimiter: char) -> Vec<Cow<'a, str>> {
         .collect()
 }
 
-pub fn parse_f64(value: &str) -> Option<f64> {
-    value.parse::<f64>().ok()
+/// Parse a mapped numeric field after applying its declared transforms.
+///
+/// Absent, blank, and sentinel values yield the transform's default (usually `None`); anything
+/// else that is not a number after stripping separators and the percent sign is an error.
+pub fn parse_mapped_value(
+    raw: Option<&str>,
+    transform: &ValueTransform,
+) -> FetchResult<Option<f64>> {
+    let raw = match raw {
+        Some(raw) if !transform.is_missing(raw) => raw.trim(),
+        _ => return Ok(transform.default),
+    };
+
+    let mut cleaned = match transform.thousands_separator {
+        Some(separator) => raw.replace(separator, ""),
+        None => raw.to_string(),
+    };
+    if transform.percent {
+        cleaned = cleaned
+            .trim_end()
+            .trim_end_matches('%')
+            .trim_end()
+            .to_string();
+    }
+
+    let value = cleaned
+        .parse::<f64>()
+        .map_err(|_| AppError::message(format!("'{}' is not a number", raw)))?;
+    Ok(Some(value * transform.scale + transform.offset))
+}
+
+/// Parse a candle timestamp; date-only formats yield midnight.
+pub fn parse_datetime(value: &str, format: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, format)
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, format)
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
 }
 
-pub fn parse_date(value: &str, format: &str) -> ParseResult<NaiveDate> {
-    NaiveDate::parse_from_str(value, format)
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::config::{RegexResponseConfig, ResponseEncoding};
+
+    #[test]
+    fn gbk_script_payloads_decode_into_named_variables() {
+        let payload =
+            "v_sh600000=\"1~浦发银行~600000~10.52\";\nvar hq_str_sz000001=\"平安银行,11.03\";";
+        let (bytes, _, _) = encoding_rs::GBK.encode(payload);
+        let text = ResponseEncoding::from_label("gbk").unwrap().decode(&bytes);
+
+        let vars = parse_js_vars(&text);
+        assert_eq!(vars.len(), 2);
+        assert_eq!(vars["v_sh600000"], "1~浦发银行~600000~10.52");
+        assert_eq!(vars["hq_str_sz000001"], "平安银行,11.03");
+    }
+
+    #[test]
+    fn empty_values_are_kept_and_unterminated_ones_skipped() {
+        let vars = parse_js_vars("v_sh600001=\"\";\nv_sh600002=\"1~cut");
+        assert_eq!(vars.get("v_sh600001"), Some(&""));
+        assert!(!vars.contains_key("v_sh600002"));
+    }
+
+    #[test]
+    fn regex_captures_keep_their_group_numbers() {
+        let pattern = RegexResponseConfig {
+            pattern: r"{symbol}:(\d+\.\d+)(?:/(\d+))?".to_string(),
+        };
+        // The code is escaped, so its dot only matches a literal dot.
+        let regex = pattern.compile("600000.SH").unwrap();
+        assert!(regex.captures("600000xSH:10.50").is_none());
+
+        let full = regex.captures("600000.SH:10.50/1200").unwrap();
+        assert_eq!(
+            capture_values(&full),
+            ["600000.SH:10.50/1200", "10.50", "1200"]
+        );
+
+        // A group that did not take part still holds its slot.
+        let partial = regex.captures("600000.SH:10.50").unwrap();
+        assert_eq!(capture_values(&partial), ["600000.SH:10.50", "10.50", ""]);
+    }
+
+    #[test]
+    fn jsonp_callbacks_are_unwrapped() {
+        assert_eq!(strip_jsonp(r#"jQuery_1({"data":[1]});"#), r#"{"data":[1]}"#);
+        assert_eq!(strip_jsonp(" cb( [1, 2] ) \n"), "[1, 2]");
+        // Plain JSON is left alone even when a string contains parentheses.
+        assert_eq!(strip_jsonp(r#" {"name":"f(x)"} "#), r#"{"name":"f(x)"}"#);
+    }
+
+    fn key(name: &str) -> JsonPathSegment {
+        JsonPathSegment::Key(name.to_string())
+    }
+
+    fn single(segments: Vec<JsonPathSegment>) -> JsonPath {
+        JsonPath {
+            alternatives: vec![segments],
+        }
+    }
+
+    #[test]
+    fn negative_indices_count_from_the_end() {
+        let root = json!({"klines": ["a", "b", "c"]});
+        let last = single(vec![key("klines"), JsonPathSegment::Index(-1)]);
+        let first = single(vec![key("klines"), JsonPathSegment::Index(-3)]);
+        let before_first = single(vec![key("klines"), JsonPathSegment::Index(-4)]);
+
+        assert_eq!(walk_json_path(&root, &last, "x", None).unwrap(), "c");
+        assert_eq!(walk_json_path(&root, &first, "x", None).unwrap(), "a");
+        let err = walk_json_path(&root, &before_first, "x", None).unwrap_err();
+        assert!(err.to_string().contains("out of bounds"));
+    }
+
+    #[test]
+    fn wildcards_take_the_first_child_the_rest_of_the_path_resolves_in() {
+        let root = json!({
+            "data": {
+                "diff": {"0": {"f2": null}, "1": {"f12": "600000", "f3": 1.5}},
+            },
+        });
+        let path = single(vec![
+            key("data"),
+            key("diff"),
+            JsonPathSegment::Wildcard,
+            key("f3"),
+        ]);
+        assert_eq!(walk_json_path(&root, &path, "600000", None).unwrap(), 1.5);
+
+        let missing = single(vec![
+            key("data"),
+            key("diff"),
+            JsonPathSegment::Wildcard,
+            key("f9"),
+        ]);
+        assert!(walk_json_path(&root, &missing, "600000", None).is_err());
+    }
+
+    #[test]
+    fn predicates_match_literals_and_either_stock_code() {
+        let root = json!({"rows": [
+            {"code": 1, "price": 9.0},
+            {"code": "sh600000", "price": 10.5},
+        ]});
+        let by_symbol = single(vec![
+            key("rows"),
+            JsonPathSegment::Predicate {
+                field: "code".to_string(),
+                value: PredicateValue::StockCode,
+            },
+            key("price"),
+        ]);
+        let by_literal = single(vec![
+            key("rows"),
+            JsonPathSegment::Predicate {
+                field: "code".to_string(),
+                value: PredicateValue::Literal("1".to_string()),
+            },
+            key("price"),
+        ]);
+
+        assert!(walk_json_path(&root, &by_symbol, "600000", None).is_err());
+        assert_eq!(
+            walk_json_path(&root, &by_symbol, "600000", Some("sh600000")).unwrap(),
+            10.5
+        );
+        // Numbers compare by their text.
+        assert_eq!(
+            walk_json_path(&root, &by_literal, "600000", None).unwrap(),
+            9.0
+        );
+    }
+
+    #[test]
+    fn alternatives_fall_through_and_report_the_last_error() {
+        let root = json!({"result": {"data": [1, 2]}});
+        let path = JsonPath {
+            alternatives: vec![
+                vec![key("data")],
+                vec![key("result"), key("data"), JsonPathSegment::Index(1)],
+            ],
+        };
+        assert_eq!(walk_json_path(&root, &path, "x", None).unwrap(), 2);
+
+        let neither = JsonPath {
+            alternatives: vec![vec![key("data")], vec![key("rows")]],
+        };
+        let err = walk_json_path(&root, &neither, "x", None).unwrap_err();
+        assert!(err.to_string().contains("`rows`"));
+    }
+
+    #[test]
+    fn separators_and_percent_are_stripped_before_scaling() {
+        let transform = ValueTransform {
+            thousands_separator: Some(','),
+            percent: true,
+            scale: 0.01,
+            ..ValueTransform::default()
+        };
+        let value = parse_mapped_value(Some(" 1,234.5 % "), &transform).unwrap();
+        assert!((value.unwrap() - 12.345).abs() < 1e-9);
+    }
+
+    #[test]
+    fn offset_is_added_after_scaling() {
+        let transform = ValueTransform {
+            scale: 1000.0,
+            offset: -5.0,
+            ..ValueTransform::default()
+        };
+        assert_eq!(
+            parse_mapped_value(Some("2"), &transform).unwrap(),
+            Some(1995.0)
+        );
+    }
+
+    #[test]
+    fn absent_blank_and_sentinel_values_use_the_default() {
+        let transform = ValueTransform {
+            missing: vec!["-".to_string(), "--".to_string()],
+            default: Some(0.0),
+            ..ValueTransform::default()
+        };
+        for raw in [None, Some(""), Some(" -- "), Some("-")] {
+            assert_eq!(parse_mapped_value(raw, &transform).unwrap(), Some(0.0));
+        }
+        assert_eq!(
+            parse_mapped_value(Some("--"), &ValueTransform::default())
+                .unwrap_err()
+                .to_string(),
+            "'--' is not a number"
+        );
+    }
+
+    #[test]
+    fn formatting_is_only_stripped_when_declared() {
+        let plain = ValueTransform::default();
+        assert!(parse_mapped_value(Some("1,234"), &plain).is_err());
+        assert!(parse_mapped_value(Some("5%"), &plain).is_err());
+    }
 }
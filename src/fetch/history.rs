@@ -1,20 +1,32 @@
 use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::future::Future;
 use std::io::Cursor;
-use std::sync::mpsc::{self, Receiver};
-use std::thread;
-use std::time::Duration;
 
 use crate::config::{
-    HistoryFieldIndices, HistoryResponse, JsonHistoryResponse, JsonHistoryRowFormat, RegionConfig,
+    HistoryConfig, HistoryFieldIndices, HistoryInterval, HistoryResponse, JsonHistoryResponse,
+    JsonHistoryRowFormat, RegexHistoryResponse, RegionConfig, ValueTransform,
 };
 use crate::error::{AppError, Context};
-use crate::fetch::decode::{parse_date, parse_f64, split_row, walk_json_path};
+use crate::fetch::client::{client_for, read_text};
+use crate::fetch::decode::{
+    capture_values, parse_datetime, parse_mapped_value, split_row, strip_jsonp, walk_json_path,
+};
+use crate::fetch::history_store::HistoryStore;
+use crate::fetch::rate_limit::limiter_for;
 use crate::fetch::request::{prepare_request, PreparedRequest, RequestContext};
+use crate::fetch::retry::{backoff_delay, classify_error};
 use crate::fetch::FetchResult;
-use chrono::{Local, LocalResult, TimeZone};
+use chrono::{DateTime, Local, LocalResult, TimeZone};
 use csv::ReaderBuilder;
-use reqwest::blocking::Client;
+use log::warn;
 use serde_json::Value;
+use tokio::sync::oneshot::{self, error::TryRecvError};
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+
+/// Candles re-requested before the last cached one so its final values replace partial ones.
+const HISTORY_OVERLAP_BARS: usize = 2;
 
 #[derive(Clone)]
 pub struct Candle {
@@ -23,29 +35,187 @@ pub struct Candle {
     pub high: f64,
     pub low: f64,
     pub close: f64,
+    pub volume: Option<f64>,
+    pub amount: Option<f64>,
+}
+
+/// History download running on the tokio runtime; dropping the task aborts the request.
+pub struct HistoryTask {
+    rx: oneshot::Receiver<FetchResult<Vec<Candle>>>,
+    handle: JoinHandle<()>,
+}
+
+impl HistoryTask {
+    /// Run `download` on the current tokio runtime.
+    pub fn spawn<F>(download: F) -> Self
+    where
+        F: Future<Output = FetchResult<Vec<Candle>>> + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let handle = tokio::spawn(async move {
+            let _ = tx.send(download.await);
+        });
+        Self { rx, handle }
+    }
+
+    /// Result of the download once it has finished, without blocking.
+    pub fn try_result(&mut self) -> Option<FetchResult<Vec<Candle>>> {
+        match self.rx.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Closed) => Some(Err(AppError::message(
+                "History fetch task ended unexpectedly",
+            ))),
+        }
+    }
+
+    pub fn abort(&self) {
+        self.handle.abort();
+    }
 }
 
-pub type HistoryReceiver = Receiver<FetchResult<Vec<Candle>>>;
+impl Drop for HistoryTask {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
 
-pub fn spawn_history_fetch(stock_code: &str, region: &RegionConfig) -> HistoryReceiver {
+/// Start downloading `interval` candles for `stock_code` on the current tokio runtime.
+pub fn spawn_history_fetch(
+    stock_code: &str,
+    region: &RegionConfig,
+    interval: HistoryInterval,
+) -> HistoryTask {
     let code = stock_code.to_string();
     let region_config = region.clone();
-    let (tx, rx) = mpsc::channel();
+    HistoryTask::spawn(async move { fetch_history(&code, &region_config, interval, None).await })
+}
 
-    thread::spawn(move || {
-        let result = fetch_history(&code, &region_config);
-        let _ = tx.send(result);
+/// Candles of one granularity for one symbol, read from the region's history cache and topped
+/// up from the provider once the cache is older than `history_max_age`.
+///
+/// `limit` overrides the interval's configured `limit` when provided.
+pub async fn fetch_history(
+    stock_code: &str,
+    region: &RegionConfig,
+    interval: HistoryInterval,
+    limit: Option<usize>,
+) -> FetchResult<Vec<Candle>> {
+    let history_cfg = region.provider.history_for(interval).ok_or_else(|| {
+        AppError::message(format!(
+            "Region {} declares no `{}` history",
+            region.code,
+            interval.label()
+        ))
+    })?;
+    let limit = limit.or(history_cfg.limit);
+    let indices = history_cfg.response.indices();
+    let store = HistoryStore::for_region(region, interval);
+    let cached = store.load(stock_code).unwrap_or_else(|err| {
+        warn!("Ignoring history cache for {}: {}", stock_code, err);
+        None
+    });
+
+    // A cache shallower than the requested depth (unless it already holds the symbol's whole
+    // history), or written before volume/amount columns were configured, is replaced by a full
+    // download.
+    let cached = cached.filter(|cached| {
+        let has = |configured: Option<usize>, value: fn(&Candle) -> Option<f64>| {
+            configured.is_none() || cached.candles.iter().any(|candle| value(candle).is_some())
+        };
+        (cached.complete || limit.is_none_or(|limit| cached.candles.len() >= limit))
+            && has(indices.volume, |candle| candle.volume)
+            && has(indices.amount, |candle| candle.amount)
     });
+    let Some(cached) = cached else {
+        let candles = download_full_history(stock_code, region, history_cfg, &store, limit).await?;
+        return Ok(take_recent(candles, limit));
+    };
+
+    if cached.is_fresh(store.max_age()) {
+        return Ok(take_recent(cached.candles, limit));
+    }
+
+    let missing_bars = cached
+        .last_timestamp()
+        .map(|last| bars_since(last, interval))
+        .unwrap_or(0)
+        + HISTORY_OVERLAP_BARS;
+    let request_limit = limit.map_or(missing_bars, |limit| missing_bars.min(limit));
+
+    let candles = match download_history(stock_code, region, history_cfg, Some(request_limit)).await
+    {
+        Ok(recent) => merge_candles(cached.candles, recent),
+        Err(err) => {
+            warn!(
+                "Serving cached history for {} after refresh failed: {}",
+                stock_code, err
+            );
+            return Ok(take_recent(cached.candles, limit));
+        }
+    };
+    // Trim the merged bars to the deepest window asked for, so repeated top-ups of intraday
+    // intervals do not grow the cache without bound.
+    let merged = candles.len();
+    let candles = take_recent(candles, limit.max(history_cfg.limit));
+    let complete = cached.complete && candles.len() == merged;
+    save_to_cache(&store, stock_code, &candles, complete);
+    Ok(take_recent(candles, limit))
+}
+
+/// Re-download the configured depth of `interval` candles for `stock_code` and replace its cache.
+/// The cached file is left untouched when the download fails.
+pub async fn rebuild_history(
+    stock_code: &str,
+    region: &RegionConfig,
+    interval: HistoryInterval,
+) -> FetchResult<Vec<Candle>> {
+    let history_cfg = region.provider.history_for(interval).ok_or_else(|| {
+        AppError::message(format!(
+            "Region {} declares no `{}` history",
+            region.code,
+            interval.label()
+        ))
+    })?;
+    let store = HistoryStore::for_region(region, interval);
+    download_full_history(stock_code, region, history_cfg, &store, history_cfg.limit).await
+}
 
-    rx
+/// Download up to `limit` candles and cache them, marking the cache complete when the provider
+/// returned fewer than requested.
+async fn download_full_history(
+    stock_code: &str,
+    region: &RegionConfig,
+    history_cfg: &HistoryConfig,
+    store: &HistoryStore,
+    limit: Option<usize>,
+) -> FetchResult<Vec<Candle>> {
+    let candles = download_history(stock_code, region, history_cfg, limit).await?;
+    let complete = limit.is_none_or(|limit| candles.len() < limit);
+    save_to_cache(store, stock_code, &candles, complete);
+    Ok(candles)
+}
+
+/// Candles elapsed since `last`, rounded up; calendar time over-counts closed sessions, which
+/// only widens the refresh request.
+fn bars_since(last: DateTime<Local>, interval: HistoryInterval) -> usize {
+    let elapsed = (Local::now() - last).num_seconds().max(0) as u64;
+    elapsed.div_ceil(interval.period().as_secs().max(1)) as usize
 }
 
-fn fetch_history(stock_code: &str, region: &RegionConfig) -> FetchResult<Vec<Candle>> {
-    let history_cfg = region.provider.history();
+/// Download and decode candles for one symbol, bypassing the cache. Everything the provider
+/// returns is kept, even beyond `limit`, so callers can tell a short history from a cut one.
+async fn download_history(
+    stock_code: &str,
+    region: &RegionConfig,
+    history_cfg: &HistoryConfig,
+    limit: Option<usize>,
+) -> FetchResult<Vec<Candle>> {
     let transformed_code = history_cfg.request.code_transform.apply(stock_code);
 
-    let mut extras: Vec<(&str, Cow<'_, str>)> = Vec::new();
-    if let Some(limit) = history_cfg.limit {
+    let mut extras: Vec<(&str, Cow<'_, str>)> =
+        vec![("interval", Cow::Borrowed(history_cfg.interval.label()))];
+    if let Some(limit) = limit {
         extras.push(("record_days", Cow::Owned(limit.to_string())));
     }
 
@@ -53,17 +223,19 @@ fn fetch_history(stock_code: &str, region: &RegionConfig) -> FetchResult<Vec<Can
         &history_cfg.request,
         RequestContext {
             stock_code,
+            symbols: &[],
             region_code: &region.code,
             extras: &extras,
         },
     )?;
 
-    let body = execute_request(stock_code, &prepared)?;
+    let body = execute_request(stock_code, region, history_cfg, &prepared).await?;
     let mut candles = match &history_cfg.response {
         HistoryResponse::JsonRows(cfg) => {
             parse_history_json(stock_code, &transformed_code, &body, cfg)?
         }
         HistoryResponse::CsvRows(cfg) => parse_history_csv(&body, cfg)?,
+        HistoryResponse::RegexRows(cfg) => parse_history_regex(&transformed_code, &body, cfg)?,
     };
 
     if candles.is_empty() {
@@ -73,36 +245,104 @@ fn fetch_history(stock_code: &str, region: &RegionConfig) -> FetchResult<Vec<Can
         )));
     }
 
-    candles.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    candles.sort_by_key(|a| a.timestamp);
+    Ok(candles)
+}
 
-    if let Some(limit) = history_cfg.limit {
+fn take_recent(mut candles: Vec<Candle>, limit: Option<usize>) -> Vec<Candle> {
+    if let Some(limit) = limit {
         if candles.len() > limit {
-            candles = candles.into_iter().rev().take(limit).collect::<Vec<_>>();
-            candles.reverse();
+            candles.drain(..candles.len() - limit);
         }
     }
+    candles
+}
 
-    Ok(candles)
+/// Overlay freshly downloaded candles on the cached ones; the download wins for shared
+/// timestamps so a bar cached mid-session is replaced by its final values.
+fn merge_candles(cached: Vec<Candle>, recent: Vec<Candle>) -> Vec<Candle> {
+    let mut by_time: BTreeMap<DateTime<Local>, Candle> = cached
+        .into_iter()
+        .map(|candle| (candle.timestamp, candle))
+        .collect();
+    for candle in recent {
+        by_time.insert(candle.timestamp, candle);
+    }
+    by_time.into_values().collect()
 }
 
-fn execute_request(stock_code: &str, prepared: &PreparedRequest) -> FetchResult<String> {
-    let client = Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-        .context("Failed to construct history HTTP client")?;
+fn save_to_cache(store: &HistoryStore, stock_code: &str, candles: &[Candle], complete: bool) {
+    if let Err(err) = store.save(stock_code, candles, complete) {
+        warn!("Failed to cache history for {}: {}", stock_code, err);
+    }
+}
+
+async fn execute_request(
+    stock_code: &str,
+    region: &RegionConfig,
+    history_cfg: &HistoryConfig,
+    prepared: &PreparedRequest,
+) -> FetchResult<String> {
+    let client = client_for(region.proxy.as_ref())?;
+    let policy = &history_cfg.request.retry;
+    let rate_limit = region.provider.rate_limit();
+    let timeout = region.fetch.history_timeout;
+    let max_attempts = policy.max_attempts.max(1);
+    let limiter = limiter_for(&prepared.url, rate_limit);
+    let mut attempt = 0;
+    let with_context = |err: reqwest::Error, what: &str| {
+        AppError::from(
+            anyhow::Error::new(prepared.redact_error(err))
+                .context(format!("{} for {}", what, stock_code)),
+        )
+    };
+
+    loop {
+        attempt += 1;
+        if let Some(limiter) = &limiter {
+            limiter.acquire().await;
+        }
+        let mut request = client
+            .request(prepared.method.clone(), &prepared.url)
+            .headers(prepared.headers.clone())
+            .timeout(timeout);
+        if let Some(body) = &prepared.body {
+            request = request.body(body.clone());
+        }
+        let failure = match request.send().await {
+            Ok(response) => {
+                let status = response.status();
+                if status.is_success() {
+                    match read_text(response, history_cfg.encoding).await {
+                        Ok(body) => return Ok(body),
+                        Err(err) if policy.retries_error(classify_error(&err)) => {
+                            with_context(err, "Failed to read history body")
+                        }
+                        Err(err) => return Err(with_context(err, "Failed to read history body")),
+                    }
+                } else {
+                    let err = AppError::message(format!(
+                        "History request returned error status {} for {}",
+                        status, stock_code
+                    ));
+                    if !policy.retries_status(status.as_u16()) {
+                        return Err(err);
+                    }
+                    err
+                }
+            }
+            Err(err) if policy.retries_error(classify_error(&err)) => {
+                with_context(err, "History request failed")
+            }
+            Err(err) => return Err(with_context(err, "History request failed")),
+        };
 
-    let response = client
-        .get(&prepared.url)
-        .headers(prepared.headers.clone())
-        .send()
-        .with_context(|| format!("History request failed for {}", stock_code))?
-        .error_for_status()
-        .with_context(|| format!("History request returned error status for {}", stock_code))?;
+        if attempt >= max_attempts {
+            return Err(failure);
+        }
 
-    response
-        .text()
-        .with_context(|| format!("Failed to read history body for {}", stock_code))
-        .map_err(AppError::from)
+        sleep(backoff_delay(policy, attempt)).await;
+    }
 }
 
 fn parse_history_json(
@@ -111,6 +351,7 @@ fn parse_history_json(
     body: &str,
     cfg: &JsonHistoryResponse,
 ) -> FetchResult<Vec<Candle>> {
+    let body = if cfg.jsonp { strip_jsonp(body) } else { body };
     let json: Value = serde_json::from_str(body)
         .with_context(|| format!("Failed to parse history JSON for {}", stock_code))?;
     let node = walk_json_path(&json, &cfg.data_path, stock_code, Some(transformed_code))?;
@@ -171,21 +412,54 @@ fn parse_history_csv(
     Ok(candles)
 }
 
+fn parse_history_regex(
+    transformed_code: &str,
+    body: &str,
+    cfg: &RegexHistoryResponse,
+) -> FetchResult<Vec<Candle>> {
+    let regex = cfg
+        .pattern
+        .compile(transformed_code)
+        .context("Invalid history response pattern")?;
+
+    let candles = regex
+        .captures_iter(body)
+        .filter_map(|captures| {
+            let parts: Vec<Cow<'_, str>> = capture_values(&captures)
+                .into_iter()
+                .map(Cow::Owned)
+                .collect();
+            candle_from_parts(&parts, &cfg.indices, &cfg.date_format)
+        })
+        .collect();
+    Ok(candles)
+}
+
 fn candle_from_parts(
     parts: &[Cow<'_, str>],
     indices: &HistoryFieldIndices,
     date_format: &str,
 ) -> Option<Candle> {
     let date = parts.get(indices.date)?.as_ref().trim();
-    let open = parse_f64(parts.get(indices.open)?.as_ref().trim())?;
-    let high = parse_f64(parts.get(indices.high)?.as_ref().trim())?;
-    let low = parse_f64(parts.get(indices.low)?.as_ref().trim())?;
-    let close = parse_f64(parts.get(indices.close)?.as_ref().trim())?;
-
-    build_candle(date, open, high, low, close, date_format)
+    let field = |idx: Option<usize>, transform: &ValueTransform| {
+        let raw = idx
+            .and_then(|idx| parts.get(idx))
+            .map(|value| value.as_ref());
+        parse_mapped_value(raw, transform).ok().flatten()
+    };
+    let transforms = &indices.transforms;
+    let open = field(Some(indices.open), &transforms.open)?;
+    let high = field(Some(indices.high), &transforms.high)?;
+    let low = field(Some(indices.low), &transforms.low)?;
+    let close = field(Some(indices.close), &transforms.close)?;
+
+    let mut candle = build_candle(date, open, high, low, close, date_format)?;
+    candle.volume = field(indices.volume, &transforms.volume);
+    candle.amount = field(indices.amount, &transforms.amount);
+    Some(candle)
 }
 
-fn build_candle(
+pub(super) fn build_candle(
     date_str: &str,
     open: f64,
     high: f64,
@@ -193,8 +467,7 @@ fn build_candle(
     close: f64,
     format: &str,
 ) -> Option<Candle> {
-    let date = parse_date(date_str, format).ok()?;
-    let naive = date.and_hms_opt(0, 0, 0)?;
+    let naive = parse_datetime(date_str, format)?;
     let timestamp = match Local.from_local_datetime(&naive) {
         LocalResult::Single(dt) => dt,
         LocalResult::Ambiguous(first, _) => first,
@@ -207,5 +480,7 @@ fn build_candle(
         high,
         low,
         close,
+        volume: None,
+        amount: None,
     })
 }
@@ -0,0 +1,262 @@
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Local};
+
+use crate::config::{HistoryInterval, RegionConfig};
+use crate::error::{AppError, Context};
+use crate::fetch::history::{build_candle, Candle};
+use crate::fetch::FetchResult;
+
+const CACHE_DATE_FMT: &str = "%Y-%m-%d";
+const CACHE_DATETIME_FMT: &str = "%Y-%m-%d %H:%M";
+const CACHE_EXTENSION: &str = "csv";
+/// Marker written next to a cache whose full download returned less than the requested depth,
+/// meaning the provider has no older candles to offer.
+const COMPLETE_EXTENSION: &str = "complete";
+
+/// Candles read back from the cache together with the time they were last written.
+#[derive(Clone)]
+pub struct CachedHistory {
+    pub candles: Vec<Candle>,
+    pub updated: SystemTime,
+    /// The cache holds the symbol's entire history, however short, so any depth is satisfied.
+    pub complete: bool,
+}
+
+impl CachedHistory {
+    /// Whether the cache was written within `max_age`.
+    pub fn is_fresh(&self, max_age: Duration) -> bool {
+        self.updated
+            .elapsed()
+            .map(|age| age <= max_age)
+            .unwrap_or(false)
+    }
+
+    /// Timestamp of the most recent cached candle.
+    pub fn last_timestamp(&self) -> Option<DateTime<Local>> {
+        self.candles.last().map(|candle| candle.timestamp)
+    }
+}
+
+/// Per-region directory of candles for one interval, one `<symbol>.csv` per instrument.
+/// Daily candles live in the history directory itself, other intervals in a subdirectory named
+/// after the interval.
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    dir: PathBuf,
+    interval: HistoryInterval,
+    max_age: Duration,
+}
+
+impl HistoryStore {
+    pub fn new(dir: impl Into<PathBuf>, interval: HistoryInterval, max_age: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            interval,
+            max_age,
+        }
+    }
+
+    pub fn for_region(region: &RegionConfig, interval: HistoryInterval) -> Self {
+        let base = &region.storage.history_dir;
+        let dir = match interval {
+            HistoryInterval::Day => base.clone(),
+            other => base.join(other.label()),
+        };
+        // An intraday series goes stale as soon as a new bar opens.
+        let max_age = if interval.is_intraday() {
+            region.fetch.history_max_age.min(interval.period())
+        } else {
+            region.fetch.history_max_age
+        };
+        Self::new(dir, interval, max_age)
+    }
+
+    pub fn interval(&self) -> HistoryInterval {
+        self.interval
+    }
+
+    fn timestamp_format(&self) -> &'static str {
+        if self.interval.is_intraday() {
+            CACHE_DATETIME_FMT
+        } else {
+            CACHE_DATE_FMT
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn max_age(&self) -> Duration {
+        self.max_age
+    }
+
+    fn path(&self, symbol: &str) -> PathBuf {
+        let name: String = symbol
+            .chars()
+            .map(|ch| match ch {
+                '/' | '\\' | ':' => '_',
+                other => other,
+            })
+            .collect();
+        self.dir.join(format!("{}.{}", name, CACHE_EXTENSION))
+    }
+
+    fn complete_marker(&self, symbol: &str) -> PathBuf {
+        self.path(symbol).with_extension(COMPLETE_EXTENSION)
+    }
+
+    /// Cached candles for `symbol`, oldest first; `None` when nothing is cached.
+    pub fn load(&self, symbol: &str) -> FetchResult<Option<CachedHistory>> {
+        let path = self.path(symbol);
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(AppError::message(format!(
+                    "Failed to open history cache {}: {}",
+                    path.display(),
+                    err
+                )))
+            }
+        };
+        let updated = file
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .with_context(|| format!("Failed to stat history cache {}", path.display()))?;
+
+        let mut reader = csv::Reader::from_reader(file);
+        let mut candles = Vec::new();
+        for record in reader.records() {
+            let record = record
+                .with_context(|| format!("Failed to read history cache {}", path.display()))?;
+            let field = |idx: usize| record.get(idx).and_then(|value| value.parse::<f64>().ok());
+            let (Some(date), Some(open), Some(high), Some(low), Some(close)) =
+                (record.get(0), field(1), field(2), field(3), field(4))
+            else {
+                continue;
+            };
+            if let Some(mut candle) =
+                build_candle(date, open, high, low, close, self.timestamp_format())
+            {
+                candle.volume = field(5);
+                candle.amount = field(6);
+                candles.push(candle);
+            }
+        }
+
+        if candles.is_empty() {
+            return Ok(None);
+        }
+        candles.sort_by_key(|candle| candle.timestamp);
+        Ok(Some(CachedHistory {
+            candles,
+            updated,
+            complete: self.complete_marker(symbol).is_file(),
+        }))
+    }
+
+    /// Replace the cached candles for `symbol`; the file is swapped in atomically. `complete`
+    /// records that the candles are the symbol's entire history.
+    pub fn save(&self, symbol: &str, candles: &[Candle], complete: bool) -> FetchResult<()> {
+        fs::create_dir_all(&self.dir).with_context(|| {
+            format!("Failed to create history directory {}", self.dir.display())
+        })?;
+
+        let path = self.path(symbol);
+        let tmp_path = path.with_extension("csv.tmp");
+        let mut writer = csv::Writer::from_path(&tmp_path)
+            .with_context(|| format!("Failed to create history cache {}", tmp_path.display()))?;
+        writer.write_record(["date", "open", "high", "low", "close", "volume", "amount"])?;
+        for candle in candles {
+            writer.write_record([
+                candle.timestamp.format(self.timestamp_format()).to_string(),
+                candle.open.to_string(),
+                candle.high.to_string(),
+                candle.low.to_string(),
+                candle.close.to_string(),
+                optional_field(candle.volume),
+                optional_field(candle.amount),
+            ])?;
+        }
+        writer.flush()?;
+        drop(writer);
+
+        fs::rename(&tmp_path, &path)
+            .with_context(|| format!("Failed to write history cache {}", path.display()))?;
+
+        let marker = self.complete_marker(symbol);
+        if complete {
+            File::create(&marker)
+                .with_context(|| format!("Failed to write history marker {}", marker.display()))?;
+        } else {
+            remove_if_exists(&marker)?;
+        }
+        Ok(())
+    }
+
+    /// Drop the cached candles for `symbol`. Returns whether a file was removed.
+    pub fn remove(&self, symbol: &str) -> FetchResult<bool> {
+        remove_if_exists(&self.complete_marker(symbol))?;
+        remove_if_exists(&self.path(symbol))
+    }
+
+    /// Symbols with a cache file, sorted.
+    pub fn symbols(&self) -> FetchResult<Vec<String>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => {
+                return Err(AppError::message(format!(
+                    "Failed to read history directory {}: {}",
+                    self.dir.display(),
+                    err
+                )))
+            }
+        };
+
+        let mut symbols = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(CACHE_EXTENSION) {
+                continue;
+            }
+            if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                symbols.push(stem.to_string());
+            }
+        }
+        symbols.sort();
+        Ok(symbols)
+    }
+
+    /// Remove every cached symbol. Returns how many files were deleted.
+    pub fn purge(&self) -> FetchResult<usize> {
+        let mut removed = 0;
+        for symbol in self.symbols()? {
+            if self.remove(&symbol)? {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+fn remove_if_exists(path: &Path) -> FetchResult<bool> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(AppError::message(format!(
+            "Failed to remove history cache {}: {}",
+            path.display(),
+            err
+        ))),
+    }
+}
+
+fn optional_field(value: Option<f64>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
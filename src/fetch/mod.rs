@@ -1,16 +1,36 @@
 use crate::error::Result;
 
+pub mod aggregate;
+pub mod checkpoint;
+pub mod client;
 mod decode;
 pub mod history;
+pub mod history_store;
+pub mod rate_limit;
+pub mod report;
 pub mod request;
+pub mod retry;
 pub mod snapshots;
 
-pub use history::{spawn_history_fetch, Candle, HistoryReceiver};
-pub use snapshots::{SnapshotFetcher, StockData};
+pub use aggregate::{aggregate_candles, AggregatePeriod};
+pub use checkpoint::{CheckpointState, FetchCheckpoint};
+pub use history::{fetch_history, rebuild_history, spawn_history_fetch, Candle, HistoryTask};
+pub use history_store::{CachedHistory, HistoryStore};
+pub use report::{FailureKind, FetchReport};
+pub use snapshots::{FetchedSnapshot, SnapshotFetcher, StockData};
 
 /// Default concurrency guard applied when issuing snapshot requests.
 pub const SNAPSHOT_CONCURRENCY_LIMIT: usize = 5;
 
+/// Default timeout for a single history request.
+pub const DEFAULT_HISTORY_TIMEOUT_SECS: u64 = 10;
+
+/// Default age after which cached history is topped up from the provider.
+pub const DEFAULT_HISTORY_MAX_AGE_SECS: u64 = 60 * 60;
+
+/// Default age after which an interrupted fetch is no longer resumed, since its quotes are stale.
+pub const DEFAULT_RESUME_MAX_AGE_SECS: u64 = 6 * 60 * 60;
+
 pub type FetchResult<T> = Result<T>;
 
 #[inline]
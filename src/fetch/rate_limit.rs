@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use reqwest::Url;
+
+use crate::config::RateLimitConfig;
+
+/// Token bucket guarding one provider host. Callers reserve a token up front, so the bucket may
+/// go negative and concurrent requests queue in arrival order instead of racing for refills.
+#[derive(Debug)]
+pub struct RateLimiter {
+    host: String,
+    bucket: Mutex<Bucket>,
+    waiting: AtomicUsize,
+    throttled: AtomicU64,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Strictest configuration registered for the host so far.
+    config: RateLimitConfig,
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Point-in-time view of a limiter, shown on the fetch progress screen.
+#[derive(Debug, Clone)]
+pub struct ThrottleState {
+    pub host: String,
+    pub requests_per_second: f64,
+    pub burst: usize,
+    /// Tokens available right now; zero while requests are queued.
+    pub available: f64,
+    /// Requests currently sleeping for a token.
+    pub waiting: usize,
+    /// Requests that had to wait since the limiter was created.
+    pub throttled: u64,
+}
+
+impl RateLimiter {
+    fn new(host: String, config: RateLimitConfig) -> Self {
+        Self {
+            host,
+            bucket: Mutex::new(Bucket {
+                config,
+                tokens: config.burst.max(1) as f64,
+                updated: Instant::now(),
+            }),
+            waiting: AtomicUsize::new(0),
+            throttled: AtomicU64::new(0),
+        }
+    }
+
+    /// Wait asynchronously until this request may be sent.
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            let _waiting = WaitingGuard::new(self);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    pub fn state(&self) -> ThrottleState {
+        let (config, available) = {
+            let mut bucket = self.lock_bucket();
+            Self::refill(&mut bucket);
+            (bucket.config, bucket.tokens.max(0.0))
+        };
+        ThrottleState {
+            host: self.host.clone(),
+            requests_per_second: config.requests_per_second,
+            burst: config.burst,
+            available,
+            waiting: self.waiting.load(Ordering::SeqCst),
+            throttled: self.throttled.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Take one token and return how long the caller must wait before using it.
+    fn reserve(&self) -> Duration {
+        let mut bucket = self.lock_bucket();
+        Self::refill(&mut bucket);
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / rate(&bucket.config))
+        }
+    }
+
+    /// Adopt the lower rate and burst of `config` without resetting the tokens already spent.
+    fn tighten(&self, config: &RateLimitConfig) {
+        let mut bucket = self.lock_bucket();
+        Self::refill(&mut bucket);
+        let strictest = RateLimitConfig {
+            requests_per_second: bucket
+                .config
+                .requests_per_second
+                .min(config.requests_per_second),
+            burst: bucket.config.burst.min(config.burst),
+        };
+        if strictest != bucket.config {
+            bucket.config = strictest;
+            bucket.tokens = bucket.tokens.min(strictest.burst.max(1) as f64);
+        }
+    }
+
+    fn refill(bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens =
+            (bucket.tokens + elapsed * rate(&bucket.config)).min(bucket.config.burst.max(1) as f64);
+        bucket.updated = now;
+    }
+
+    fn lock_bucket(&self) -> std::sync::MutexGuard<'_, Bucket> {
+        self.bucket
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn rate(config: &RateLimitConfig) -> f64 {
+    // Invalid rates are rejected by the validator; keep a floor so a bad value cannot divide by
+    // zero here.
+    config.requests_per_second.max(f64::EPSILON)
+}
+
+/// Tracks a sleeping request, and un-tracks it even when the waiting future is dropped.
+struct WaitingGuard<'a> {
+    limiter: &'a RateLimiter,
+}
+
+impl<'a> WaitingGuard<'a> {
+    fn new(limiter: &'a RateLimiter) -> Self {
+        limiter.waiting.fetch_add(1, Ordering::SeqCst);
+        limiter.throttled.fetch_add(1, Ordering::SeqCst);
+        Self { limiter }
+    }
+}
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        self.limiter.waiting.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn registry() -> &'static Mutex<HashMap<String, Arc<RateLimiter>>> {
+    static LIMITERS: OnceLock<Mutex<HashMap<String, Arc<RateLimiter>>>> = OnceLock::new();
+    LIMITERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Limiter shared by every request to the host of `url`, so snapshot and history fetches (and
+/// any other job) draw from the same bucket. When several configurations share a host, the bucket
+/// keeps the strictest rate and burst among them rather than being replaced.
+pub fn limiter_for(url: &str, config: Option<&RateLimitConfig>) -> Option<Arc<RateLimiter>> {
+    let config = config?;
+    let host = Url::parse(url).ok()?.host_str()?.to_ascii_lowercase();
+
+    let mut limiters = registry()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(limiter) = limiters.get(&host) {
+        limiter.tighten(config);
+        return Some(limiter.clone());
+    }
+
+    let limiter = Arc::new(RateLimiter::new(host.clone(), *config));
+    limiters.insert(host, limiter.clone());
+    Some(limiter)
+}
+
+/// Current state of every limiter that has seen a request, ordered by host.
+pub fn throttle_states() -> Vec<ThrottleState> {
+    let limiters: Vec<Arc<RateLimiter>> = registry()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .values()
+        .cloned()
+        .collect();
+    let mut states: Vec<ThrottleState> = limiters.iter().map(|limiter| limiter.state()).collect();
+    states.sort_by(|a, b| a.host.cmp(&b.host));
+    states
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(requests_per_second: f64, burst: usize) -> RateLimiter {
+        RateLimiter::new(
+            "quotes.example.com".to_string(),
+            RateLimitConfig {
+                requests_per_second,
+                burst,
+            },
+        )
+    }
+
+    #[test]
+    fn burst_goes_out_immediately_then_requests_queue() {
+        let limiter = limiter(2.0, 3);
+        for _ in 0..3 {
+            assert_eq!(limiter.reserve(), Duration::ZERO);
+        }
+
+        // Each queued request waits half a second longer than the one before it.
+        let fourth = limiter.reserve().as_secs_f64();
+        let fifth = limiter.reserve().as_secs_f64();
+        assert!((fourth - 0.5).abs() < 0.05, "{fourth}");
+        assert!((fifth - 1.0).abs() < 0.05, "{fifth}");
+        assert_eq!(limiter.state().available, 0.0);
+    }
+
+    #[test]
+    fn tokens_refill_with_time_up_to_the_burst() {
+        let limiter = limiter(4.0, 2);
+        {
+            let mut bucket = limiter.lock_bucket();
+            bucket.tokens = -1.0;
+            bucket.updated = Instant::now() - Duration::from_millis(500);
+        }
+        let available = limiter.state().available;
+        assert!((available - 1.0).abs() < 0.05, "{available}");
+
+        limiter.lock_bucket().updated = Instant::now() - Duration::from_secs(60);
+        assert_eq!(limiter.state().available, 2.0);
+    }
+
+    #[test]
+    fn tightening_keeps_spent_tokens_spent() {
+        let limiter = limiter(1.0, 10);
+        for _ in 0..12 {
+            limiter.reserve();
+        }
+
+        limiter.tighten(&RateLimitConfig {
+            requests_per_second: 5.0,
+            burst: 4,
+        });
+        let state = limiter.state();
+        assert_eq!((state.requests_per_second, state.burst), (1.0, 4));
+        // Two requests are already queued, so the next one waits three seconds.
+        let wait = limiter.reserve().as_secs_f64();
+        assert!((wait - 3.0).abs() < 0.05, "{wait}");
+    }
+
+    #[test]
+    fn hosts_share_one_limiter_at_the_strictest_config() {
+        let loose = RateLimitConfig {
+            requests_per_second: 10.0,
+            burst: 2,
+        };
+        let strict = RateLimitConfig {
+            requests_per_second: 1.0,
+            burst: 5,
+        };
+        let snapshot = limiter_for("https://Shared.Limiter.test/quote", Some(&loose)).unwrap();
+        let history = limiter_for("https://shared.limiter.test/kline?n=1", Some(&strict)).unwrap();
+        assert!(Arc::ptr_eq(&snapshot, &history));
+
+        let state = snapshot.state();
+        assert_eq!(state.host, "shared.limiter.test");
+        assert_eq!((state.requests_per_second, state.burst), (1.0, 2));
+
+        assert!(limiter_for("https://shared.limiter.test/", None).is_none());
+        assert!(limiter_for("not a url", Some(&loose)).is_none());
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::error::AppError;
+
+/// Coarse cause of a symbol that could not be fetched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// Blocked by the provider (HTTP 403 or the configured firewall marker).
+    Firewall,
+    /// Non-success HTTP status, including exhausted retries on retryable statuses.
+    HttpStatus,
+    /// Connection, timeout, or other transport failure.
+    Network,
+    /// Payload could not be parsed or lacked a usable price.
+    Decode,
+    /// The configured JSON path or delimited row was absent for the symbol.
+    MissingPath,
+    /// Request could not be prepared from the region config.
+    Request,
+}
+
+impl FailureKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            FailureKind::Firewall => "firewall",
+            FailureKind::HttpStatus => "http status",
+            FailureKind::Network => "network",
+            FailureKind::Decode => "decode error",
+            FailureKind::MissingPath => "missing path",
+            FailureKind::Request => "request config",
+        }
+    }
+}
+
+/// Classified error for one symbol (or one batch) of a snapshot fetch.
+#[derive(Debug, Clone)]
+pub struct FetchFailure {
+    pub kind: FailureKind,
+    pub message: String,
+}
+
+impl FetchFailure {
+    pub fn new(kind: FailureKind, message: impl fmt::Display) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for FetchFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind.label(), self.message)
+    }
+}
+
+impl From<FetchFailure> for AppError {
+    fn from(failure: FetchFailure) -> Self {
+        AppError::message(failure.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolFailure {
+    pub symbol: String,
+    pub message: String,
+}
+
+/// Outcome of a snapshot fetch per symbol, persisted next to the saved snapshot.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FetchReport {
+    pub region: String,
+    pub total: usize,
+    pub succeeded: Vec<String>,
+    pub failures: BTreeMap<FailureKind, Vec<SymbolFailure>>,
+}
+
+impl FetchReport {
+    pub fn new(region: impl Into<String>, total: usize) -> Self {
+        Self {
+            region: region.into(),
+            total,
+            ..Self::default()
+        }
+    }
+
+    pub fn record_success(&mut self, symbol: &str) {
+        self.succeeded.push(symbol.to_string());
+    }
+
+    pub fn record_failure(&mut self, symbol: &str, failure: FetchFailure) {
+        self.failures
+            .entry(failure.kind)
+            .or_default()
+            .push(SymbolFailure {
+                symbol: symbol.to_string(),
+                message: failure.message,
+            });
+    }
+
+    /// Sort symbols so reports from unordered concurrent fetches are stable.
+    pub fn finish(&mut self) {
+        self.succeeded.sort();
+        for failures in self.failures.values_mut() {
+            failures.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        }
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.failures.values().map(Vec::len).sum()
+    }
+
+    /// Failure counts by cause, e.g. `firewall 3, http status 1`.
+    pub fn failure_summary(&self) -> String {
+        self.failures
+            .iter()
+            .map(|(kind, failures)| format!("{} {}", kind.label(), failures.len()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// One line per cause listing up to `max_symbols` affected symbols.
+    pub fn failure_lines(&self, max_symbols: usize) -> Vec<String> {
+        self.failures
+            .iter()
+            .map(|(kind, failures)| {
+                let mut symbols = failures
+                    .iter()
+                    .take(max_symbols)
+                    .map(|failure| failure.symbol.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if failures.len() > max_symbols {
+                    symbols.push_str(&format!(", +{} more", failures.len() - max_symbols));
+                }
+                format!("{} ({}): {}", kind.label(), failures.len(), symbols)
+            })
+            .collect()
+    }
+}
@@ -1,21 +1,69 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::fs;
+use std::iter::Peekable;
+use std::path::PathBuf;
+use std::str::Chars;
 
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::{Method, Url};
+use serde_json::Value;
 
-use crate::config::{HttpMethod, RequestConfig};
+use crate::config::{BodyContentType, HttpMethod, RequestBody, RequestConfig};
 use crate::error::{AppError, Context};
 
 use super::FetchResult;
 
+/// Shown in place of expanded secrets in errors.
+const SECRET_MASK: &str = "***";
+
+/// Placeholder prefix that reads a secret from a file instead of the environment.
+const FILE_PLACEHOLDER: &str = "file:";
+
+/// Everything but RFC 3986 unreserved characters is escaped in values substituted into URLs, so
+/// a `&`, `=`, `+`, `#`, or `/` inside a code or token cannot restructure the URL.
+const URL_VALUE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
 #[derive(Debug, Clone)]
 pub struct PreparedRequest {
+    pub method: Method,
     pub url: String,
     pub headers: HeaderMap,
+    /// Encoded body for POST/PUT requests; the matching `Content-Type` is already in `headers`.
+    pub body: Option<String>,
+    /// Values substituted for `${...}` placeholders, masked wherever the request is reported.
+    secrets: Vec<String>,
+}
+
+impl PreparedRequest {
+    /// Replace every expanded secret in `text` with a mask.
+    fn redact(&self, text: &str) -> String {
+        self.secrets.iter().fold(text.to_string(), |text, secret| {
+            text.replace(secret, SECRET_MASK)
+        })
+    }
+
+    /// Mask secrets in the URL a transport error reports, dropping the URL if masking breaks it.
+    pub fn redact_error(&self, err: reqwest::Error) -> reqwest::Error {
+        if self.secrets.is_empty() || err.url().is_none() {
+            return err;
+        }
+        match Url::parse(&self.redact(&self.url)) {
+            Ok(url) => err.with_url(url),
+            Err(_) => err.without_url(),
+        }
+    }
 }
 
 pub struct RequestContext<'a> {
     pub stock_code: &'a str,
+    /// Every symbol in a batched request, rendered into `{symbols}`; empty for single requests.
+    pub symbols: &'a [String],
     pub region_code: &'a str,
     pub extras: &'a [(&'a str, Cow<'a, str>)],
 }
@@ -24,16 +72,25 @@ pub fn prepare_request(
     request: &RequestConfig,
     context: RequestContext<'_>,
 ) -> FetchResult<PreparedRequest> {
-    if !matches!(request.method, HttpMethod::Get) {
-        return Err(AppError::message("Unsupported HTTP method"));
-    }
-
     let transformed_code = request.code_transform.apply(context.stock_code);
 
     let mut replacements: HashMap<String, String> = HashMap::new();
     replacements.insert("code".to_string(), transformed_code.clone());
     replacements.insert("symbol".to_string(), transformed_code.clone());
     replacements.insert("raw_code".to_string(), context.stock_code.to_string());
+    let symbol_codes = if context.symbols.is_empty() {
+        vec![transformed_code.clone()]
+    } else {
+        context
+            .symbols
+            .iter()
+            .map(|code| request.code_transform.apply(code))
+            .collect::<Vec<_>>()
+    };
+    replacements.insert(
+        "symbols".to_string(),
+        symbol_codes.join(&request.symbols_joiner),
+    );
     replacements.insert("region".to_string(), context.region_code.to_string());
     replacements.insert(
         "region_lower".to_string(),
@@ -44,49 +101,128 @@ pub fn prepare_request(
         replacements.insert((*key).to_string(), value.clone().into_owned());
     }
 
-    let url = render_template(&request.url_template, &replacements)?;
-    let headers = build_headers(&request.headers)?;
+    // URL values are escaped one by one; the joiner between batched codes stays as written.
+    let mut url_replacements: HashMap<String, String> = replacements
+        .iter()
+        .map(|(key, value)| (key.clone(), encode_url_value(value)))
+        .collect();
+    url_replacements.insert(
+        "symbols".to_string(),
+        symbol_codes
+            .iter()
+            .map(|code| encode_url_value(code))
+            .collect::<Vec<_>>()
+            .join(&request.symbols_joiner),
+    );
+
+    let mut secrets = Vec::new();
+    let url = render_template(
+        &request.url_template,
+        &url_replacements,
+        TemplateTarget::Url,
+        &mut secrets,
+    )?;
+    let mut headers = build_headers(&request.headers, &mut secrets)?;
+    let body = match &request.body {
+        Some(body) => {
+            if !headers.contains_key(CONTENT_TYPE) {
+                headers.insert(
+                    CONTENT_TYPE,
+                    HeaderValue::from_static(body.content_type.mime()),
+                );
+            }
+            Some(render_body(body, &replacements, &mut secrets)?)
+        }
+        None => None,
+    };
 
-    Ok(PreparedRequest { url, headers })
+    let method = match request.method {
+        HttpMethod::Get => Method::GET,
+        HttpMethod::Post => Method::POST,
+        HttpMethod::Put => Method::PUT,
+    };
+
+    Ok(PreparedRequest {
+        method,
+        url,
+        headers,
+        body,
+        secrets,
+    })
 }
 
+fn render_body(
+    body: &RequestBody,
+    replacements: &HashMap<String, String>,
+    secrets: &mut Vec<String>,
+) -> FetchResult<String> {
+    let rendered = render_value(&body.template, replacements, secrets)?;
+    match body.content_type {
+        BodyContentType::Json => {
+            serde_json::to_string(&rendered).context("Failed to encode JSON request body")
+        }
+        BodyContentType::Form => {
+            let fields = rendered
+                .as_object()
+                .ok_or_else(|| AppError::message("Form request body template must be an object"))?
+                .iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    };
+                    (key.clone(), value)
+                })
+                .collect::<Vec<_>>();
+            serde_urlencoded::to_string(&fields).context("Failed to encode form request body")
+        }
+    }
+    .map_err(AppError::from)
+}
+
+/// Render every string in a JSON template; keys and non-string values are kept as written.
+fn render_value(
+    template: &Value,
+    replacements: &HashMap<String, String>,
+    secrets: &mut Vec<String>,
+) -> FetchResult<Value> {
+    Ok(match template {
+        Value::String(text) => Value::String(render_template(
+            text,
+            replacements,
+            TemplateTarget::Body,
+            secrets,
+        )?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| render_value(item, replacements, secrets))
+                .collect::<FetchResult<Vec<_>>>()?,
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), render_value(value, replacements, secrets)?)))
+                .collect::<FetchResult<serde_json::Map<_, _>>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+/// Expand `${ENV}` and `${file:path}` placeholders in a configuration value.
 pub fn expand_env_vars(value: &str) -> FetchResult<String> {
+    expand_secrets(value, &mut Vec::new())
+}
+
+/// Like [`expand_env_vars`], also recording each substituted value in `secrets`.
+fn expand_secrets(value: &str, secrets: &mut Vec<String>) -> FetchResult<String> {
     let mut result = String::with_capacity(value.len());
     let mut chars = value.chars().peekable();
 
     while let Some(ch) = chars.next() {
         if ch == '$' && matches!(chars.peek(), Some('{')) {
             chars.next();
-            let mut name = String::new();
-            let mut closed = false;
-            while let Some(&next) = chars.peek() {
-                chars.next();
-                if next == '}' {
-                    closed = true;
-                    break;
-                }
-                name.push(next);
-            }
-
-            if name.is_empty() {
-                return Err(AppError::message(
-                    "Encountered empty environment placeholder in header",
-                ));
-            }
-
-            if !closed {
-                return Err(AppError::message(
-                    "Unterminated environment placeholder in header",
-                ));
-            }
-
-            let value = std::env::var(&name).with_context(|| {
-                format!(
-                    "Environment variable {} required by request header is not set",
-                    name
-                )
-            })?;
-            result.push_str(&value);
+            result.push_str(&read_secret(&mut chars, secrets)?);
         } else {
             result.push(ch);
         }
@@ -95,22 +231,104 @@ pub fn expand_env_vars(value: &str) -> FetchResult<String> {
     Ok(result)
 }
 
-fn render_template(template: &str, replacements: &HashMap<String, String>) -> FetchResult<String> {
+/// Read the rest of a `${...}` placeholder and resolve it from a file or the environment.
+fn read_secret(chars: &mut Peekable<Chars<'_>>, secrets: &mut Vec<String>) -> FetchResult<String> {
+    let (name, closed) = read_placeholder(chars);
+
+    if name.is_empty() {
+        return Err(AppError::message(
+            "Encountered empty environment placeholder",
+        ));
+    }
+
+    if !closed {
+        return Err(AppError::message("Unterminated environment placeholder"));
+    }
+
+    let value = match name.strip_prefix(FILE_PLACEHOLDER) {
+        Some(path) => {
+            let path = expand_home(path.trim());
+            fs::read_to_string(&path)
+                .with_context(|| {
+                    format!(
+                        "Failed to read secret file {} referenced in the configuration",
+                        path.display()
+                    )
+                })?
+                .trim_end_matches(['\r', '\n'])
+                .to_string()
+        }
+        None => std::env::var(&name).with_context(|| {
+            format!(
+                "Environment variable {} referenced in the configuration is not set",
+                name
+            )
+        })?,
+    };
+    if !value.is_empty() && !secrets.contains(&value) {
+        secrets.push(value.clone());
+    }
+    Ok(value)
+}
+
+/// Collect placeholder text up to the closing brace; the flag is false when none was found.
+fn read_placeholder(chars: &mut Peekable<Chars<'_>>) -> (String, bool) {
+    let mut name = String::new();
+    for next in chars.by_ref() {
+        if next == '}' {
+            return (name, true);
+        }
+        name.push(next);
+    }
+    (name, false)
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Where a rendered template ends up, which decides how expanded secrets are escaped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemplateTarget {
+    Url,
+    Body,
+}
+
+fn encode_url_value(value: &str) -> String {
+    utf8_percent_encode(value, URL_VALUE).to_string()
+}
+
+/// Render `{placeholder}` replacements and `${...}` secrets in a URL or body template. URL
+/// replacements arrive already escaped; secrets are escaped here, and their escaped form is
+/// recorded too so masking matches what the request actually carried.
+fn render_template(
+    template: &str,
+    replacements: &HashMap<String, String>,
+    target: TemplateTarget,
+    secrets: &mut Vec<String>,
+) -> FetchResult<String> {
     let mut result = String::with_capacity(template.len());
     let mut chars = template.chars().peekable();
 
     while let Some(ch) = chars.next() {
-        if ch == '{' {
-            let mut key = String::new();
-            let mut closed = false;
-            while let Some(&next) = chars.peek() {
-                chars.next();
-                if next == '}' {
-                    closed = true;
-                    break;
+        if ch == '$' && matches!(chars.peek(), Some('{')) {
+            chars.next();
+            let secret = read_secret(&mut chars, secrets)?;
+            match target {
+                TemplateTarget::Url => {
+                    let encoded = encode_url_value(&secret);
+                    if encoded != secret && !secrets.contains(&encoded) {
+                        secrets.push(encoded.clone());
+                    }
+                    result.push_str(&encoded);
                 }
-                key.push(next);
+                TemplateTarget::Body => result.push_str(&secret),
             }
+        } else if ch == '{' {
+            let (key, closed) = read_placeholder(&mut chars);
 
             if !closed {
                 return Err(AppError::message(format!(
@@ -139,15 +357,155 @@ fn render_template(template: &str, replacements: &HashMap<String, String>) -> Fe
     Ok(result)
 }
 
-fn build_headers(headers: &HashMap<String, String>) -> FetchResult<HeaderMap> {
+fn build_headers(
+    headers: &HashMap<String, String>,
+    secrets: &mut Vec<String>,
+) -> FetchResult<HeaderMap> {
     let mut map = HeaderMap::new();
     for (key, value) in headers {
         let name = HeaderName::from_bytes(key.as_bytes())
             .with_context(|| format!("Invalid header name: {}", key))?;
-        let expanded = expand_env_vars(value)?;
+        let expanded = expand_secrets(value, secrets)?;
         let header_value = HeaderValue::from_str(&expanded)
             .with_context(|| format!("Invalid header value for {}", key))?;
         map.insert(name, header_value);
     }
     Ok(map)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CodeTransform, RetryPolicy};
+
+    /// Secret file removed when the test ends. Tests read secrets from files rather than setting
+    /// environment variables, which would race with other test threads.
+    struct SecretFile(PathBuf);
+
+    impl SecretFile {
+        fn new(name: &str, value: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "stock-cli-test-{}-{}",
+                name,
+                std::process::id()
+            ));
+            fs::write(&path, value).unwrap();
+            Self(path)
+        }
+
+        fn placeholder(&self) -> String {
+            format!("${{file:{}}}", self.0.display())
+        }
+    }
+
+    impl Drop for SecretFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn request(url_template: &str, body: Option<Value>) -> RequestConfig {
+        RequestConfig {
+            method: HttpMethod::Post,
+            url_template: url_template.to_string(),
+            // Cargo sets `CARGO_PKG_NAME` for test binaries, so no test has to set a variable.
+            headers: HashMap::from([(
+                "Authorization".to_string(),
+                "Bearer ${CARGO_PKG_NAME}".to_string(),
+            )]),
+            code_transform: CodeTransform::default(),
+            symbols_joiner: ",".to_string(),
+            body: body.map(|template| RequestBody {
+                content_type: BodyContentType::Json,
+                template,
+            }),
+            retry: RetryPolicy::snapshot_default(),
+        }
+    }
+
+    fn prepare(request: &RequestConfig, symbols: &[String]) -> PreparedRequest {
+        prepare_request(
+            request,
+            RequestContext {
+                stock_code: "A&B",
+                symbols,
+                region_code: "CN",
+                extras: &[],
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn url_values_and_secrets_are_percent_encoded() {
+        let token = SecretFile::new("url-token", "a+b/c=d&e#f");
+        let symbols = ["X&Y".to_string(), "Z=1".to_string()];
+        let prepared = prepare(
+            &request(
+                &format!(
+                    "https://example.com/q?list={{symbols}}&key={}",
+                    token.placeholder()
+                ),
+                None,
+            ),
+            &symbols,
+        );
+        assert_eq!(
+            prepared.url,
+            "https://example.com/q?list=X%26Y,Z%3D1&key=a%2Bb%2Fc%3Dd%26e%23f"
+        );
+        assert_eq!(
+            prepared.headers["Authorization"],
+            format!("Bearer {}", env!("CARGO_PKG_NAME"))
+        );
+    }
+
+    #[test]
+    fn body_values_and_secrets_stay_verbatim() {
+        let token = SecretFile::new("body-token", "p+q/r");
+        let template = serde_json::json!({
+            "code": "{symbol}",
+            "token": token.placeholder(),
+        });
+        let prepared = prepare(&request("https://example.com/q", Some(template)), &[]);
+        let body: Value = serde_json::from_str(prepared.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["code"], "A&B");
+        assert_eq!(body["token"], "p+q/r");
+    }
+
+    #[test]
+    fn redaction_masks_raw_and_encoded_secrets() {
+        let token = SecretFile::new("mask-token", "k+y=");
+        let prepared = prepare(
+            &request(
+                &format!("https://example.com/q?key={}", token.placeholder()),
+                None,
+            ),
+            &[],
+        );
+        assert_eq!(
+            prepared.redact(&prepared.url),
+            "https://example.com/q?key=***"
+        );
+        assert_eq!(prepared.redact("token k+y= leaked"), "token *** leaked");
+    }
+
+    #[test]
+    fn file_secrets_drop_the_trailing_newline() {
+        let secret = SecretFile::new("newline", "from-file\n");
+        let expanded = expand_env_vars(&format!("x={}", secret.placeholder())).unwrap();
+        assert_eq!(expanded, "x=from-file");
+    }
+
+    #[test]
+    fn malformed_placeholders_are_rejected() {
+        for template in ["${}", "${UNTERMINATED", "{symbol"] {
+            let mut secrets = Vec::new();
+            assert!(
+                render_template(template, &HashMap::new(), TemplateTarget::Url, &mut secrets)
+                    .is_err(),
+                "{template}"
+            );
+        }
+    }
+}
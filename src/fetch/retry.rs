@@ -0,0 +1,103 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::{RetryErrorKind, RetryPolicy};
+
+/// Delay before the next attempt once `failures` attempts have failed (1-based), doubling from
+/// the base delay, capped at the maximum, and spread by the configured jitter.
+pub fn backoff_delay(policy: &RetryPolicy, failures: usize) -> Duration {
+    let exponent = failures.saturating_sub(1).min(16) as u32;
+    let delay = policy
+        .base_delay
+        .saturating_mul(2u32.pow(exponent))
+        .min(policy.max_delay);
+
+    let jitter = policy.jitter.clamp(0.0, 1.0);
+    if jitter == 0.0 {
+        return delay;
+    }
+
+    let spread = delay.as_secs_f64() * jitter;
+    let offset = (unit_random() * 2.0 - 1.0) * spread;
+    Duration::from_secs_f64((delay.as_secs_f64() + offset).max(0.0)).min(policy.max_delay)
+}
+
+/// Map a transport error onto the kinds a retry policy can opt into.
+pub fn classify_error(err: &reqwest::Error) -> RetryErrorKind {
+    if err.is_timeout() {
+        RetryErrorKind::Timeout
+    } else if err.is_connect() {
+        RetryErrorKind::Connect
+    } else if err.is_body() || err.is_decode() {
+        RetryErrorKind::Body
+    } else {
+        RetryErrorKind::Request
+    }
+}
+
+/// Uniform value in `[0, 1)`; randomly keyed hashing is plenty for spreading retries.
+fn unit_random() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    hasher.write_u128(nanos);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_until_the_maximum_delay() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+            jitter: 0.0,
+            ..RetryPolicy::snapshot_default()
+        };
+
+        let delays: Vec<u64> = (1..=6)
+            .map(|failures| backoff_delay(&policy, failures).as_secs())
+            .collect();
+        assert_eq!(delays, [1, 2, 4, 8, 10, 10]);
+        assert_eq!(backoff_delay(&policy, 0), policy.base_delay);
+        assert_eq!(backoff_delay(&policy, usize::MAX), policy.max_delay);
+    }
+
+    #[test]
+    fn jitter_spreads_around_the_delay_without_passing_the_maximum() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_secs(4),
+            max_delay: Duration::from_secs(10),
+            jitter: 0.5,
+            ..RetryPolicy::snapshot_default()
+        };
+
+        for _ in 0..100 {
+            let first = backoff_delay(&policy, 1);
+            assert!(first >= Duration::from_secs(2) && first <= Duration::from_secs(6));
+
+            // 8s spread by half lands in 4s..12s, and the cap trims the top.
+            let second = backoff_delay(&policy, 2);
+            assert!(second >= Duration::from_secs(4) && second <= policy.max_delay);
+        }
+    }
+
+    #[test]
+    fn jitter_above_one_is_clamped() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_secs(2),
+            max_delay: Duration::from_secs(60),
+            jitter: 5.0,
+            ..RetryPolicy::snapshot_default()
+        };
+
+        for _ in 0..100 {
+            assert!(backoff_delay(&policy, 1) <= Duration::from_secs(4));
+        }
+    }
+}
@@ -2,18 +2,32 @@ use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
-use crate::config::{DelimitedResponseConfig, RegionConfig, SnapshotConfig, SnapshotResponse};
+use crate::config::{
+    DelimitedResponseConfig, InfoIndex, InfoSource, JsVarsResponseConfig, RegexResponseConfig,
+    RegionConfig, SnapshotConfig, SnapshotResponse,
+};
 use crate::error::{AppError, Context};
 use futures::stream::{self, StreamExt};
+use log::warn;
 use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tokio::time::{sleep, Duration};
-
-use crate::fetch::decode::{split_csv_line, value_to_string, walk_json_path};
+use tokio::time::sleep;
+
+use crate::fetch::checkpoint::FetchCheckpoint;
+use crate::fetch::client::{client_for, read_text};
+use crate::fetch::decode::{
+    capture_values, parse_js_vars, parse_mapped_value, split_csv_line, strip_jsonp,
+    value_to_string, walk_json_path,
+};
+use crate::fetch::rate_limit::limiter_for;
+use crate::fetch::report::{FailureKind, FetchFailure, FetchReport};
 use crate::fetch::request::{prepare_request, PreparedRequest, RequestContext};
-use crate::fetch::{ensure_concurrency_limit, FetchResult, SNAPSHOT_CONCURRENCY_LIMIT};
+use crate::fetch::retry::{backoff_delay, classify_error};
+use crate::fetch::{ensure_concurrency_limit, FetchResult};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 /// Canonical representation of a single stock row returned by the remote endpoint.
 pub struct StockData {
     pub market: String,
@@ -30,6 +44,16 @@ pub struct StockData {
     pub tm: f64,
 }
 
+/// Per-symbol outcome inside the snapshot pipeline.
+type SymbolResult<T> = Result<T, FetchFailure>;
+
+/// Rows fetched for a region together with the per-symbol report.
+#[derive(Debug, Clone)]
+pub struct FetchedSnapshot {
+    pub data: Vec<StockData>,
+    pub report: FetchReport,
+}
+
 /// Fetches stock snapshots concurrently while exposing a shared progress counter for the UI.
 pub struct SnapshotFetcher {
     pub stock_list: Vec<String>,
@@ -39,6 +63,7 @@ pub struct SnapshotFetcher {
     pub progress_counter: Arc<AtomicUsize>,
     pub total_stocks: usize,
     concurrency_limit: usize,
+    checkpoint: Option<Arc<FetchCheckpoint>>,
 }
 
 impl SnapshotFetcher {
@@ -46,13 +71,9 @@ impl SnapshotFetcher {
         stock_list: Vec<String>,
         region_config: RegionConfig,
         static_names: HashMap<String, String>,
-    ) -> Self {
-        Self::with_concurrency_limit(
-            stock_list,
-            region_config,
-            static_names,
-            SNAPSHOT_CONCURRENCY_LIMIT,
-        )
+    ) -> FetchResult<Self> {
+        let concurrency_limit = region_config.fetch.concurrency;
+        Self::with_concurrency_limit(stock_list, region_config, static_names, concurrency_limit)
     }
 
     pub fn with_concurrency_limit(
@@ -60,159 +81,333 @@ impl SnapshotFetcher {
         region_config: RegionConfig,
         static_names: HashMap<String, String>,
         concurrency_limit: usize,
-    ) -> Self {
+    ) -> FetchResult<Self> {
         let total_stocks = stock_list.len();
-        Self {
+        let client = client_for(region_config.proxy.as_ref())?;
+        Ok(Self {
             stock_list,
             region_config,
             static_names: Arc::new(static_names),
-            client: Client::new(),
+            client,
             progress_counter: Arc::new(AtomicUsize::new(0)),
             total_stocks,
             concurrency_limit: ensure_concurrency_limit(concurrency_limit),
-        }
+            checkpoint: None,
+        })
+    }
+
+    /// Journal completed rows to `checkpoint` as each batch finishes.
+    pub fn with_checkpoint(mut self, checkpoint: FetchCheckpoint) -> Self {
+        self.checkpoint = Some(Arc::new(checkpoint));
+        self
     }
 
-    pub async fn fetch_data(&self) -> FetchResult<Vec<StockData>> {
+    /// Fetch every symbol, failing only when none could be fetched; per-symbol failures are
+    /// collected in the returned report.
+    pub async fn fetch_data(&self) -> FetchResult<FetchedSnapshot> {
         let concurrency_limit = self.concurrency_limit;
         let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency_limit));
         let progress_counter = Arc::clone(&self.progress_counter);
+        let batch_size = self.snapshot_config().batch_size.max(1);
 
         progress_counter.store(0, Ordering::SeqCst);
 
-        let results: Vec<Option<StockData>> = stream::iter(self.stock_list.clone().into_iter())
-            .map(|stock_code_owned| {
+        let batches: Vec<Vec<String>> = self
+            .stock_list
+            .chunks(batch_size)
+            .map(<[String]>::to_vec)
+            .collect();
+
+        let results: Vec<Vec<(String, SymbolResult<StockData>)>> = stream::iter(batches)
+            .map(|batch| {
                 let semaphore = Arc::clone(&semaphore);
                 let progress_counter = Arc::clone(&progress_counter);
                 let this = self;
                 async move {
                     let _permit = semaphore.acquire().await.unwrap();
-                    let result = this.fetch_stock_data(&stock_code_owned).await;
+                    let results = this.fetch_batch(&batch).await;
+                    if let Some(checkpoint) = &this.checkpoint {
+                        if let Err(err) = checkpoint.record(results.iter().flatten()) {
+                            warn!("Failed to update fetch checkpoint: {}", err);
+                        }
+                    }
 
-                    let _current = progress_counter.fetch_add(1, Ordering::SeqCst) + 1;
+                    progress_counter.fetch_add(batch.len(), Ordering::SeqCst);
 
-                    result.ok()
+                    batch.into_iter().zip(results).collect::<Vec<_>>()
                 }
             })
             .buffer_unordered(concurrency_limit)
             .collect()
             .await;
 
-        let valid_results: Vec<StockData> = results.into_iter().flatten().collect();
-
-        if valid_results.is_empty() {
-            return Err(AppError::message("Failed to fetch any stock data"));
+        let mut report = FetchReport::new(&self.region_config.code, self.total_stocks);
+        let mut data = Vec::with_capacity(self.total_stocks);
+        for (code, result) in results.into_iter().flatten() {
+            match result {
+                Ok(stock) => {
+                    report.record_success(&code);
+                    data.push(stock);
+                }
+                Err(failure) => report.record_failure(&code, failure),
+            }
+        }
+        report.finish();
+
+        if data.is_empty() {
+            let summary = report.failure_summary();
+            return Err(if summary.is_empty() {
+                AppError::message("Failed to fetch any stock data")
+            } else {
+                AppError::message(format!("Failed to fetch any stock data ({})", summary))
+            });
         }
 
-        Ok(valid_results)
+        Ok(FetchedSnapshot { data, report })
     }
 
     fn snapshot_config(&self) -> &SnapshotConfig {
         self.region_config.provider.snapshot()
     }
 
-    async fn fetch_stock_data(&self, stock_code: &str) -> FetchResult<StockData> {
+    /// Fetch one batch of symbols, yielding one result per symbol in request order.
+    async fn fetch_batch(&self, codes: &[String]) -> Vec<SymbolResult<StockData>> {
+        if let [code] = codes {
+            return vec![self.fetch_stock_data(code).await];
+        }
+
+        match self.fetch_batch_data(codes).await {
+            Ok(results) => results,
+            Err(failure) => {
+                warn!("Batch starting at {} failed: {}", codes[0], failure);
+                vec![Err(failure); codes.len()]
+            }
+        }
+    }
+
+    async fn fetch_stock_data(&self, stock_code: &str) -> SymbolResult<StockData> {
         let snapshot_cfg = self.snapshot_config();
         let prepared = prepare_request(
             &snapshot_cfg.request,
             RequestContext {
                 stock_code,
+                symbols: &[],
                 region_code: &self.region_config.code,
                 extras: &[],
             },
-        )?;
+        )
+        .map_err(|err| FetchFailure::new(FailureKind::Request, err))?;
         let response_text = self.perform_request(&prepared, stock_code).await?;
         validate_firewall(&response_text, snapshot_cfg)?;
-        let values = parse_response(stock_code, &response_text, &snapshot_cfg.response)?;
+        let row = parse_response(stock_code, &response_text, snapshot_cfg)?;
         build_stock_data(
             stock_code,
             &self.region_config,
             snapshot_cfg,
-            &values,
+            &row,
             &self.static_names,
         )
+        .map_err(|err| FetchFailure::new(FailureKind::Decode, err))
+    }
+
+    /// Issue one request for several symbols and split the combined payload per symbol.
+    async fn fetch_batch_data(
+        &self,
+        codes: &[String],
+    ) -> SymbolResult<Vec<SymbolResult<StockData>>> {
+        let snapshot_cfg = self.snapshot_config();
+        let prepared = prepare_request(
+            &snapshot_cfg.request,
+            RequestContext {
+                stock_code: &codes[0],
+                symbols: codes,
+                region_code: &self.region_config.code,
+                extras: &[],
+            },
+        )
+        .map_err(|err| FetchFailure::new(FailureKind::Request, err))?;
+        let label = format!("{} (+{} more)", codes[0], codes.len() - 1);
+        let response_text = self.perform_request(&prepared, &label).await?;
+        validate_firewall(&response_text, snapshot_cfg)?;
+        let rows = split_batch_response(codes, &response_text, snapshot_cfg)?;
+
+        Ok(codes
+            .iter()
+            .zip(rows)
+            .map(|(code, row)| {
+                row.and_then(|row| {
+                    build_stock_data(
+                        code,
+                        &self.region_config,
+                        snapshot_cfg,
+                        &row,
+                        &self.static_names,
+                    )
+                    .map_err(|err| FetchFailure::new(FailureKind::Decode, err))
+                })
+            })
+            .collect())
     }
 
     async fn perform_request(
         &self,
         prepared: &PreparedRequest,
         stock_code: &str,
-    ) -> FetchResult<String> {
-        let mut retry_count = 0;
-        let max_retries = 3;
+    ) -> SymbolResult<String> {
+        let policy = &self.snapshot_config().request.retry;
+        let max_attempts = self
+            .region_config
+            .fetch
+            .max_attempts
+            .unwrap_or(policy.max_attempts)
+            .max(1);
+        let limiter = limiter_for(&prepared.url, self.region_config.provider.rate_limit());
+        let mut attempt = 0;
 
         loop {
-            let request = self
+            attempt += 1;
+            if let Some(limiter) = &limiter {
+                limiter.acquire().await;
+            }
+            let mut request = self
                 .client
-                .get(&prepared.url)
+                .request(prepared.method.clone(), &prepared.url)
                 .headers(prepared.headers.clone());
-            match request.send().await {
+            if let Some(body) = &prepared.body {
+                request = request.body(body.clone());
+            }
+            let failure = match request
+                .send()
+                .await
+                .map_err(|err| prepared.redact_error(err))
+            {
                 Ok(response) => {
-                    if response.status().is_redirection() {
-                        return Err(AppError::message(format!(
-                            "Request for stock {} was redirected",
-                            stock_code
-                        )));
-                    }
-
-                    if response.status() == StatusCode::FORBIDDEN {
-                        return Err(AppError::message(format!(
-                            "Request for stock {} was blocked by firewall",
-                            stock_code
-                        )));
-                    }
-
-                    if response.status().is_success() {
-                        return response
-                            .text()
+                    let status = response.status();
+                    if status.is_success() {
+                        match read_text(response, self.snapshot_config().encoding)
                             .await
-                            .context("Failed to read response body")
-                            .map_err(AppError::from);
-                    }
-
-                    retry_count += 1;
-                    if retry_count >= max_retries {
-                        return Err(AppError::message(format!(
-                            "Request for stock {} failed with status {}",
-                            stock_code,
-                            response.status()
-                        )));
+                            .map_err(|err| prepared.redact_error(err))
+                        {
+                            Ok(body) => return Ok(body),
+                            Err(err) if policy.retries_error(classify_error(&err)) => {
+                                FetchFailure::new(
+                                    FailureKind::Network,
+                                    format!("failed to read response body: {}", err),
+                                )
+                            }
+                            Err(err) => {
+                                return Err(FetchFailure::new(
+                                    FailureKind::Network,
+                                    format!(
+                                        "Failed to read response body for stock {}: {}",
+                                        stock_code, err
+                                    ),
+                                ));
+                            }
+                        }
+                    } else if policy.retries_status(status.as_u16()) {
+                        FetchFailure::new(FailureKind::HttpStatus, format!("status {}", status))
+                    } else if status.is_redirection() {
+                        return Err(FetchFailure::new(
+                            FailureKind::HttpStatus,
+                            format!("Request for stock {} was redirected", stock_code),
+                        ));
+                    } else if status == StatusCode::FORBIDDEN {
+                        return Err(FetchFailure::new(
+                            FailureKind::Firewall,
+                            format!("Request for stock {} was blocked by firewall", stock_code),
+                        ));
+                    } else {
+                        return Err(FetchFailure::new(
+                            FailureKind::HttpStatus,
+                            format!(
+                                "Request for stock {} failed with status {}",
+                                stock_code, status
+                            ),
+                        ));
                     }
                 }
+                Err(err) if policy.retries_error(classify_error(&err)) => {
+                    FetchFailure::new(FailureKind::Network, err)
+                }
                 Err(err) => {
-                    retry_count += 1;
-                    if retry_count >= max_retries {
-                        return Err(AppError::message(format!(
-                            "Failed to fetch stock {} after {} retries: {}",
-                            stock_code, max_retries, err
-                        )));
-                    }
+                    return Err(FetchFailure::new(
+                        FailureKind::Network,
+                        format!("Failed to fetch stock {}: {}", stock_code, err),
+                    ));
                 }
+            };
+
+            if attempt >= max_attempts {
+                return Err(FetchFailure::new(
+                    failure.kind,
+                    format!(
+                        "Failed to fetch stock {} after {} attempt(s): {}",
+                        stock_code, attempt, failure.message
+                    ),
+                ));
             }
 
-            let delay = Duration::from_millis(2_u64.pow(retry_count as u32) * 1000);
-            sleep(delay).await;
+            sleep(backoff_delay(policy, attempt)).await;
         }
     }
 }
 
-fn validate_firewall(response_text: &str, snapshot_cfg: &SnapshotConfig) -> FetchResult<()> {
+fn validate_firewall(response_text: &str, snapshot_cfg: &SnapshotConfig) -> SymbolResult<()> {
     if let Some(warning) = &snapshot_cfg.firewall_warning {
         if response_text.contains(&warning.text) {
-            return Err(AppError::message("Request was blocked by firewall"));
+            return Err(FetchFailure::new(
+                FailureKind::Firewall,
+                "Request was blocked by firewall",
+            ));
         }
     }
     Ok(())
 }
 
+/// Per-symbol payload that `info_indices` are resolved against.
+enum SnapshotRow {
+    /// Positional fields of a delimited line, script variable, or regex match.
+    Fields(Vec<String>),
+    /// The JSON node selected by the response path, an array or an object.
+    Json(Value),
+}
+
+impl SnapshotRow {
+    fn get(&self, index: &InfoIndex, stock_code: &str) -> Option<String> {
+        match (self, &index.source) {
+            (SnapshotRow::Fields(values), InfoSource::Position(idx)) => values.get(*idx).cloned(),
+            (SnapshotRow::Json(node), InfoSource::Position(idx)) => {
+                node.get(*idx).map(value_to_string)
+            }
+            (SnapshotRow::Json(node), InfoSource::Path(path)) => {
+                walk_json_path(node, path, stock_code, None)
+                    .ok()
+                    .map(value_to_string)
+            }
+            (SnapshotRow::Fields(_), InfoSource::Path(_)) => None,
+        }
+    }
+}
+
 fn parse_response(
     stock_code: &str,
     text: &str,
-    response: &SnapshotResponse,
-) -> FetchResult<Vec<String>> {
-    match response {
+    snapshot_cfg: &SnapshotConfig,
+) -> SymbolResult<SnapshotRow> {
+    match &snapshot_cfg.response {
         SnapshotResponse::Json(cfg) => parse_json_response(stock_code, text, cfg),
-        SnapshotResponse::Delimited(cfg) => parse_delimited_response(text, cfg),
+        SnapshotResponse::Delimited(cfg) => {
+            parse_delimited_response(text, cfg).map(SnapshotRow::Fields)
+        }
+        SnapshotResponse::JsVars(cfg) => {
+            let transformed = snapshot_cfg.request.code_transform.apply(stock_code);
+            js_var_values(&parse_js_vars(text), &transformed, cfg).map(SnapshotRow::Fields)
+        }
+        SnapshotResponse::Regex(cfg) => {
+            let transformed = snapshot_cfg.request.code_transform.apply(stock_code);
+            regex_values(text, &transformed, cfg).map(SnapshotRow::Fields)
+        }
     }
 }
 
@@ -220,21 +415,149 @@ fn parse_json_response(
     stock_code: &str,
     text: &str,
     cfg: &crate::config::JsonResponseConfig,
-) -> FetchResult<Vec<String>> {
-    let json: Value = serde_json::from_str(text).context("Failed to parse JSON response")?;
-    let node = walk_json_path(&json, &cfg.data_path, stock_code, None)?;
-    let array = node
-        .as_array()
-        .ok_or_else(|| AppError::message("Snapshot payload was not an array of values"))?;
-    Ok(array.iter().map(value_to_string).collect())
+) -> SymbolResult<SnapshotRow> {
+    let json = parse_json(text, cfg.jsonp)?;
+    json_node(&json, stock_code, None, cfg)
+}
+
+fn json_node(
+    json: &Value,
+    stock_code: &str,
+    transformed_code: Option<&str>,
+    cfg: &crate::config::JsonResponseConfig,
+) -> SymbolResult<SnapshotRow> {
+    let node = walk_json_path(json, &cfg.data_path, stock_code, transformed_code)
+        .map_err(|err| FetchFailure::new(FailureKind::MissingPath, err))?;
+    if !node.is_array() && !node.is_object() {
+        return Err(FetchFailure::new(
+            FailureKind::Decode,
+            "Snapshot payload was neither an array nor an object",
+        ));
+    }
+    Ok(SnapshotRow::Json(node.clone()))
+}
+
+fn js_var_values(
+    vars: &HashMap<&str, &str>,
+    transformed_code: &str,
+    cfg: &JsVarsResponseConfig,
+) -> SymbolResult<Vec<String>> {
+    let name = cfg.variable_for(transformed_code);
+    let payload = vars
+        .get(name.as_str())
+        .filter(|payload| !payload.trim().is_empty())
+        .ok_or_else(|| {
+            FetchFailure::new(
+                FailureKind::MissingPath,
+                format!("No quote data returned in `{}`", name),
+            )
+        })?;
+    Ok(payload.split(cfg.delimiter).map(str::to_string).collect())
+}
+
+fn regex_values(
+    text: &str,
+    transformed_code: &str,
+    cfg: &RegexResponseConfig,
+) -> SymbolResult<Vec<String>> {
+    let regex = cfg.compile(transformed_code).map_err(|err| {
+        FetchFailure::new(
+            FailureKind::Decode,
+            format!("Invalid response pattern: {}", err),
+        )
+    })?;
+    let captures = regex.captures(text).ok_or_else(|| {
+        FetchFailure::new(
+            FailureKind::MissingPath,
+            format!("Response pattern did not match for {}", transformed_code),
+        )
+    })?;
+    Ok(capture_values(&captures))
+}
+
+fn parse_json(text: &str, jsonp: bool) -> SymbolResult<Value> {
+    let text = if jsonp { strip_jsonp(text) } else { text };
+    serde_json::from_str(text).map_err(|err| {
+        FetchFailure::new(
+            FailureKind::Decode,
+            format!("Failed to parse JSON response: {}", err),
+        )
+    })
 }
 
-fn parse_delimited_response(text: &str, cfg: &DelimitedResponseConfig) -> FetchResult<Vec<String>> {
+/// Split a batched payload into one value row per requested symbol, in request order.
+///
+/// JSON payloads are walked once per symbol through the `{symbol}` path segments, script-style
+/// payloads are looked up by each symbol's variable name, regex patterns are matched once per
+/// symbol, and delimited payloads are expected to carry one non-empty line per symbol.
+fn split_batch_response(
+    codes: &[String],
+    text: &str,
+    snapshot_cfg: &SnapshotConfig,
+) -> SymbolResult<Vec<SymbolResult<SnapshotRow>>> {
+    match &snapshot_cfg.response {
+        SnapshotResponse::Json(cfg) => {
+            let json = parse_json(text, cfg.jsonp)?;
+            Ok(codes
+                .iter()
+                .map(|code| {
+                    let transformed = snapshot_cfg.request.code_transform.apply(code);
+                    json_node(&json, code, Some(&transformed), cfg)
+                })
+                .collect())
+        }
+        SnapshotResponse::JsVars(cfg) => {
+            let vars = parse_js_vars(text);
+            Ok(codes
+                .iter()
+                .map(|code| {
+                    let transformed = snapshot_cfg.request.code_transform.apply(code);
+                    js_var_values(&vars, &transformed, cfg).map(SnapshotRow::Fields)
+                })
+                .collect())
+        }
+        SnapshotResponse::Regex(cfg) => Ok(codes
+            .iter()
+            .map(|code| {
+                let transformed = snapshot_cfg.request.code_transform.apply(code);
+                regex_values(text, &transformed, cfg).map(SnapshotRow::Fields)
+            })
+            .collect()),
+        SnapshotResponse::Delimited(cfg) => {
+            let mut lines = text
+                .lines()
+                .skip(cfg.skip_lines)
+                .filter(|line| !line.trim().is_empty());
+            Ok(codes
+                .iter()
+                .map(|code| {
+                    let line = lines.next().ok_or_else(|| {
+                        FetchFailure::new(
+                            FailureKind::MissingPath,
+                            format!("No quote data returned for {}", code),
+                        )
+                    })?;
+                    Ok(SnapshotRow::Fields(
+                        split_csv_line(line, cfg.delimiter)
+                            .into_iter()
+                            .map(|field| field.into_owned())
+                            .collect(),
+                    ))
+                })
+                .collect())
+        }
+    }
+}
+
+fn parse_delimited_response(
+    text: &str,
+    cfg: &DelimitedResponseConfig,
+) -> SymbolResult<Vec<String>> {
     let line = text
         .lines()
         .skip(cfg.skip_lines)
         .find(|line| !line.trim().is_empty())
-        .context("No quote data returned")?;
+        .ok_or_else(|| FetchFailure::new(FailureKind::MissingPath, "No quote data returned"))?;
 
     Ok(split_csv_line(line, cfg.delimiter)
         .into_iter()
@@ -246,27 +569,23 @@ fn build_stock_data(
     stock_code: &str,
     region_config: &RegionConfig,
     snapshot_cfg: &SnapshotConfig,
-    values: &[String],
+    row: &SnapshotRow,
     static_names: &HashMap<String, String>,
 ) -> FetchResult<StockData> {
     let lookup_value = |key: &str| -> Option<String> {
-        snapshot_cfg
-            .info_idxs
-            .get(key)
-            .and_then(|idx| values.get(idx.index))
+        let index = snapshot_cfg.info_idxs.get(key)?;
+        row.get(index, stock_code)
+            .filter(|value| !index.transform.is_missing(value))
             .map(|value| value.trim().to_string())
     };
 
     let parse_float = |key: &str| -> FetchResult<Option<f64>> {
-        match lookup_value(key) {
-            Some(value) if !value.is_empty() => value
-                .parse::<f64>()
-                .with_context(|| format!("Failed to parse {} as float", key))
-                .map(Some)
-                .map_err(AppError::from),
-            Some(_) => Ok(None),
-            None => Ok(None),
-        }
+        let Some(index) = snapshot_cfg.info_idxs.get(key) else {
+            return Ok(None);
+        };
+        parse_mapped_value(row.get(index, stock_code).as_deref(), &index.transform)
+            .with_context(|| format!("Failed to parse {} as float", key))
+            .map_err(AppError::from)
     };
 
     let curr = parse_float("curr")?
@@ -291,17 +610,9 @@ fn build_stock_data(
         None => amplitude(highest, lowest, prev_closed),
     };
 
-    let volume = parse_float("volume")?;
-
-    let turn_over = match parse_float("turnOver")? {
-        Some(value) => value,
-        None => volume.map(|v| v / 1_000_000.0).unwrap_or(0.0),
-    };
-
-    let tm = match parse_float("tm")? {
-        Some(value) => value,
-        None => volume.map(|v| (v * curr) / 1_000_000.0).unwrap_or(0.0),
-    };
+    // Unit conversion is left to each field's `scale`; unmapped fields read as zero.
+    let turn_over = parse_float("turnOver")?.unwrap_or(0.0);
+    let tm = parse_float("tm")?.unwrap_or(0.0);
 
     Ok(StockData {
         market: region_config.code.clone(),
@@ -1,63 +1,450 @@
 use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
 
 use env_logger::Env;
 use log::{info, LevelFilter};
 use stock_cli::app;
+use stock_cli::cli::{
+    self, CacheAction, DaemonOptions, FetchOptions, HistoryCacheOptions, HistoryFormat,
+    HistoryOptions, ProgressFormat, ScreenFormat, ScreenOptions, ValidateOptions,
+};
+use stock_cli::config::{FetchOverrides, HistoryInterval, Settings, Workspace};
 use stock_cli::error::{AppError, Result};
+use stock_cli::fetch::AggregatePeriod;
+use stock_cli::records::SortField;
+use stock_cli::utils::{parse_interval, Schedule};
 
 #[derive(Debug, Clone)]
 struct CliOptions {
+    globals: GlobalOptions,
+    command: Command,
+}
+
+/// Options accepted before or after any subcommand.
+#[derive(Debug, Clone, Default)]
+struct GlobalOptions {
     log_level: Option<LevelFilter>,
+    root: Option<PathBuf>,
+    configs_dir: Option<PathBuf>,
+    fetch: FetchOverrides,
+}
+
+#[derive(Debug, Clone)]
+enum Command {
+    Tui,
+    Fetch(FetchOptions),
+    Screen(ScreenOptions),
+    Validate(ValidateOptions),
+    History(HistoryOptions),
+    HistoryCache(HistoryCacheOptions),
+    Daemon(DaemonOptions),
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let cli = parse_cli_options()?;
-    init_logging(cli.log_level)?;
-    info!("Starting stock-cli");
-    app::run().await
+async fn main() -> Result<ExitCode> {
+    let options = parse_cli_options()?;
+    init_logging(options.globals.log_level)?;
+    let workspace = Workspace::resolve(options.globals.root, options.globals.configs_dir)?;
+    let settings = Settings::load(&workspace)?.with_cli_overrides(options.globals.fetch);
+    match options.command {
+        Command::Tui => {
+            info!("Starting stock-cli in {}", workspace.root().display());
+            app::run(workspace, settings).await?;
+        }
+        Command::Fetch(fetch) => cli::run_fetch(&workspace, &settings, fetch).await?,
+        Command::Screen(screen) => cli::run_screen(&workspace, &settings, screen).await?,
+        Command::History(history) => cli::run_history(&workspace, &settings, history).await?,
+        Command::HistoryCache(cache) => {
+            cli::run_history_cache(&workspace, &settings, cache).await?
+        }
+        Command::Daemon(daemon) => cli::run_daemon(&workspace, &settings, daemon).await?,
+        Command::Validate(validate) => return cli::run_validate(&workspace, validate),
+    }
+    Ok(ExitCode::SUCCESS)
 }
 
 fn parse_cli_options() -> Result<CliOptions> {
     let mut args = env::args().skip(1);
-    let mut log_level = None;
+    let mut globals = GlobalOptions::default();
+    let mut command = Command::Tui;
 
     while let Some(arg) = args.next() {
+        if parse_global_option(&arg, &mut args, &mut globals)? {
+            continue;
+        }
+
         match arg.as_str() {
-            "--log-level" => {
-                let value = args.next().ok_or_else(|| {
-                    AppError::message(
-                        "--log-level requires a value (error, warn, info, debug, trace)",
-                    )
-                })?;
-                let level = value.parse::<LevelFilter>().map_err(|_| {
+            "fetch" => {
+                command = Command::Fetch(parse_fetch_options(&mut args, &mut globals)?);
+            }
+            "screen" => {
+                command = Command::Screen(parse_screen_options(&mut args, &mut globals)?);
+            }
+            "history" => {
+                command = Command::History(parse_history_options(&mut args, &mut globals)?);
+            }
+            "history-cache" => {
+                command =
+                    Command::HistoryCache(parse_history_cache_options(&mut args, &mut globals)?);
+            }
+            "daemon" => {
+                command = Command::Daemon(parse_daemon_options(&mut args, &mut globals)?);
+            }
+            "validate" => {
+                command = Command::Validate(parse_validate_options(&mut args, &mut globals)?);
+            }
+            other if other.starts_with('-') => {
+                return Err(AppError::message(format!("Unknown option '{}'", other)));
+            }
+            other => {
+                return Err(AppError::message(format!(
+                    "Unexpected argument '{}'",
+                    other
+                )));
+            }
+        }
+    }
+
+    Ok(CliOptions { globals, command })
+}
+
+/// Handle options accepted before or after any subcommand. Returns `true` when `arg` was consumed.
+fn parse_global_option(
+    arg: &str,
+    args: &mut impl Iterator<Item = String>,
+    globals: &mut GlobalOptions,
+) -> Result<bool> {
+    match arg {
+        "--log-level" => {
+            let value = args.next().ok_or_else(|| {
+                AppError::message("--log-level requires a value (error, warn, info, debug, trace)")
+            })?;
+            let level = value.parse::<LevelFilter>().map_err(|_| {
+                AppError::message(format!(
+                    "Invalid log level '{}'. Expected error, warn, info, debug, or trace.",
+                    value
+                ))
+            })?;
+            globals.log_level = Some(level);
+        }
+        "--quiet" | "-q" => {
+            globals.log_level = Some(LevelFilter::Warn);
+        }
+        "--root" => globals.root = Some(PathBuf::from(option_value(args, "--root")?)),
+        "--configs-dir" => {
+            globals.configs_dir = Some(PathBuf::from(option_value(args, "--configs-dir")?));
+        }
+        "--concurrency" => {
+            globals.fetch.concurrency = Some(positive_value(args, "--concurrency")?);
+        }
+        "--max-attempts" => {
+            globals.fetch.max_attempts = Some(positive_value(args, "--max-attempts")?);
+        }
+        "--history-timeout" => {
+            globals.fetch.history_timeout_secs =
+                Some(positive_value(args, "--history-timeout")? as u64);
+        }
+        "--help" | "-h" => {
+            print_usage();
+            std::process::exit(0);
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn parse_fetch_options(
+    args: &mut impl Iterator<Item = String>,
+    globals: &mut GlobalOptions,
+) -> Result<FetchOptions> {
+    let mut region = None;
+    let mut format = ProgressFormat::Text;
+    let mut resume = false;
+
+    while let Some(arg) = args.next() {
+        if parse_global_option(&arg, args, globals)? {
+            continue;
+        }
+
+        match arg.as_str() {
+            "--region" => region = Some(option_value(args, "--region")?),
+            "--format" => format = option_value(args, "--format")?.parse()?,
+            "--resume" => resume = true,
+            other => {
+                return Err(AppError::message(format!(
+                    "Unknown fetch option '{}'",
+                    other
+                )));
+            }
+        }
+    }
+
+    Ok(FetchOptions {
+        region,
+        format,
+        resume,
+    })
+}
+
+fn parse_screen_options(
+    args: &mut impl Iterator<Item = String>,
+    globals: &mut GlobalOptions,
+) -> Result<ScreenOptions> {
+    let mut region = None;
+    let mut preset = None;
+    let mut snapshot = None;
+    let mut format = ScreenFormat::Table;
+    let mut sort = SortField::LastPrice;
+    let mut descending = true;
+
+    while let Some(arg) = args.next() {
+        if parse_global_option(&arg, args, globals)? {
+            continue;
+        }
+
+        match arg.as_str() {
+            "--region" => region = Some(option_value(args, "--region")?),
+            "--preset" => preset = Some(option_value(args, "--preset")?),
+            "--snapshot" => snapshot = Some(option_value(args, "--snapshot")?),
+            "--format" => format = option_value(args, "--format")?.parse()?,
+            "--sort" => {
+                let value = option_value(args, "--sort")?;
+                sort = SortField::from_metric_key(&value).ok_or_else(|| {
+                    let keys = SortField::ALL
+                        .iter()
+                        .map(|field| field.metric_key())
+                        .collect::<Vec<_>>()
+                        .join(", ");
                     AppError::message(format!(
-                        "Invalid log level '{}'. Expected error, warn, info, debug, or trace.",
-                        value
+                        "Invalid sort metric '{}'. Expected one of: {}.",
+                        value, keys
                     ))
                 })?;
-                log_level = Some(level);
             }
-            "--quiet" | "-q" => {
-                log_level = Some(LevelFilter::Warn);
+            "--ascending" => descending = false,
+            "--descending" => descending = true,
+            other => {
+                return Err(AppError::message(format!(
+                    "Unknown screen option '{}'",
+                    other
+                )));
+            }
+        }
+    }
+
+    Ok(ScreenOptions {
+        region,
+        preset,
+        snapshot,
+        format,
+        sort,
+        descending,
+    })
+}
+
+fn parse_validate_options(
+    args: &mut impl Iterator<Item = String>,
+    globals: &mut GlobalOptions,
+) -> Result<ValidateOptions> {
+    let mut options = ValidateOptions { json: false };
+
+    while let Some(arg) = args.next() {
+        if parse_global_option(&arg, args, globals)? {
+            continue;
+        }
+
+        match arg.as_str() {
+            "--json" => options.json = true,
+            other => {
+                return Err(AppError::message(format!(
+                    "Unknown validate option '{}'",
+                    other
+                )));
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+fn parse_history_options(
+    args: &mut impl Iterator<Item = String>,
+    globals: &mut GlobalOptions,
+) -> Result<HistoryOptions> {
+    let mut region = None;
+    let mut symbols = Vec::new();
+    let mut symbols_file = None;
+    let mut interval = HistoryInterval::Day;
+    let mut aggregate = None;
+    let mut limit = None;
+    let mut format = HistoryFormat::Csv;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        if parse_global_option(&arg, args, globals)? {
+            continue;
+        }
+
+        match arg.as_str() {
+            "--region" => region = Some(option_value(args, "--region")?),
+            "--symbol" => symbols.push(option_value(args, "--symbol")?),
+            "--symbols-file" => {
+                symbols_file = Some(PathBuf::from(option_value(args, "--symbols-file")?));
             }
-            "--help" | "-h" => {
-                print_usage();
-                std::process::exit(0);
+            "--interval" => interval = history_interval_value(args, "--interval")?,
+            "--aggregate" => {
+                let value = option_value(args, "--aggregate")?;
+                aggregate = Some(AggregatePeriod::from_label(&value).ok_or_else(|| {
+                    AppError::message(format!(
+                        "Invalid value '{}' for --aggregate. Expected week or month.",
+                        value
+                    ))
+                })?);
+            }
+            "--limit" => limit = Some(positive_value(args, "--limit")?),
+            "--format" => format = option_value(args, "--format")?.parse()?,
+            "--output" | "-o" => output = Some(PathBuf::from(option_value(args, "--output")?)),
+            other => {
+                return Err(AppError::message(format!(
+                    "Unknown history option '{}'",
+                    other
+                )));
             }
+        }
+    }
+
+    Ok(HistoryOptions {
+        region,
+        symbols,
+        symbols_file,
+        interval,
+        aggregate,
+        limit,
+        format,
+        output,
+    })
+}
+
+fn parse_history_cache_options(
+    args: &mut impl Iterator<Item = String>,
+    globals: &mut GlobalOptions,
+) -> Result<HistoryCacheOptions> {
+    let mut region = None;
+    let mut action: Option<CacheAction> = None;
+    let mut symbols = Vec::new();
+    let mut intervals = Vec::new();
+
+    while let Some(arg) = args.next() {
+        if parse_global_option(&arg, args, globals)? {
+            continue;
+        }
+
+        match arg.as_str() {
+            "--region" => region = Some(option_value(args, "--region")?),
+            "--symbol" => symbols.push(option_value(args, "--symbol")?),
+            "--interval" => intervals.push(history_interval_value(args, "--interval")?),
             other if other.starts_with('-') => {
-                return Err(AppError::message(format!("Unknown option '{}'", other)));
+                return Err(AppError::message(format!(
+                    "Unknown history-cache option '{}'",
+                    other
+                )));
             }
+            other => {
+                if action.replace(other.parse()?).is_some() {
+                    return Err(AppError::message(
+                        "history-cache accepts a single purge or rebuild action",
+                    ));
+                }
+            }
+        }
+    }
+
+    let action = action
+        .ok_or_else(|| AppError::message("history-cache requires an action: purge or rebuild"))?;
+    Ok(HistoryCacheOptions {
+        region,
+        action,
+        symbols,
+        intervals,
+    })
+}
+
+fn parse_daemon_options(
+    args: &mut impl Iterator<Item = String>,
+    globals: &mut GlobalOptions,
+) -> Result<DaemonOptions> {
+    let mut regions = Vec::new();
+    let mut schedule = None;
+    let mut ignore_sessions = false;
+
+    while let Some(arg) = args.next() {
+        if parse_global_option(&arg, args, globals)? {
+            continue;
+        }
+
+        let next = match arg.as_str() {
+            "--region" => {
+                regions.push(option_value(args, "--region")?);
+                continue;
+            }
+            "--ignore-sessions" => {
+                ignore_sessions = true;
+                continue;
+            }
+            "--interval" => Schedule::Interval(parse_interval(&option_value(args, "--interval")?)?),
+            "--cron" => Schedule::Cron(option_value(args, "--cron")?.parse()?),
             other => {
                 return Err(AppError::message(format!(
-                    "Unexpected argument '{}'",
+                    "Unknown daemon option '{}'",
                     other
                 )));
             }
+        };
+        if schedule.replace(next).is_some() {
+            return Err(AppError::message(
+                "daemon accepts a single --interval or --cron schedule",
+            ));
         }
     }
 
-    Ok(CliOptions { log_level })
+    Ok(DaemonOptions {
+        regions,
+        schedule: schedule.unwrap_or(Schedule::Interval(cli::DEFAULT_DAEMON_INTERVAL)),
+        ignore_sessions,
+    })
+}
+
+fn positive_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<usize> {
+    let value = option_value(args, flag)?;
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or_else(|| {
+            AppError::message(format!(
+                "Invalid value '{}' for {}. Expected a positive number.",
+                value, flag
+            ))
+        })
+}
+
+fn history_interval_value(
+    args: &mut impl Iterator<Item = String>,
+    flag: &str,
+) -> Result<HistoryInterval> {
+    let value = option_value(args, flag)?;
+    HistoryInterval::from_label(&value).ok_or_else(|| {
+        AppError::message(format!(
+            "Invalid value '{}' for {}. Expected 1m, 5m, 30m, day, week, or month.",
+            value, flag
+        ))
+    })
+}
+
+fn option_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String> {
+    args.next()
+        .ok_or_else(|| AppError::message(format!("{} requires a value", flag)))
 }
 
 fn init_logging(level: Option<LevelFilter>) -> Result<()> {
@@ -78,6 +465,6 @@ fn init_logging(level: Option<LevelFilter>) -> Result<()> {
 
 fn print_usage() {
     println!(
-        "Stock CLI\n\nUSAGE:\n    stock-cli [OPTIONS]\n\nOPTIONS:\n    --log-level <LEVEL>    Override the default log level (error, warn, info, debug, trace)\n    -q, --quiet            Reduce logging noise (equivalent to --log-level warn)\n    -h, --help             Show this help message\n\nEnvironment variables:\n    RUST_LOG               Standard env_logger filter string."
+        "Stock CLI\n\nUSAGE:\n    stock-cli [OPTIONS]\n    stock-cli fetch [--region <CODE>] [--format text|json] [--resume]\n    stock-cli screen [--region <CODE>] [--preset <NAME>] [--snapshot <FILE>] [--format table|csv|json] [--sort <METRIC>] [--ascending]\n    stock-cli history [--region <CODE>] (--symbol <CODE>... | --symbols-file <FILE>) [--interval <INTERVAL>] [--aggregate week|month] [--limit N] [--format csv|json] [--output <FILE>]\n    stock-cli history-cache (purge|rebuild) [--region <CODE>] [--symbol <CODE>...] [--interval <INTERVAL>...]\n    stock-cli daemon [--region <CODE>...] [--interval <DURATION> | --cron <EXPR>] [--ignore-sessions]\n    stock-cli validate [--json]\n\nCOMMANDS:\n    fetch                  Snapshot a region without the TUI and save it under its snapshots directory\n    screen                 Apply a threshold preset to a snapshot and print the matching rows\n    history                Download candles for one or more symbols\n    history-cache          Purge or re-download the on-disk history cache of a region\n    daemon                 Snapshot regions on a schedule during their trading sessions\n    validate               Check every assets/configs/*.json file; exits with status 2 when any has errors\n\nOPTIONS:\n    --log-level <LEVEL>    Override the default log level (error, warn, info, debug, trace)\n    -q, --quiet            Reduce logging noise (equivalent to --log-level warn)\n    --root <DIR>           Workspace root holding assets/ (default: $STOCK_CLI_HOME, the current\n                           directory when it has assets/configs, else ~/.local/share/stock-cli)\n    --configs-dir <DIR>    Region descriptor directory (default: <root>/assets/configs)\n    --concurrency <N>      Parallel snapshot requests (overrides settings.json)\n    --max-attempts <N>     Attempts per snapshot request (overrides settings.json)\n    --history-timeout <S>  History request timeout in seconds (overrides settings.json)\n    -h, --help             Show this help message\n\nFETCH OPTIONS:\n    --region <CODE>        Region code declared in assets/configs (default: settings default_region)\n    --format <FORMAT>      Progress output: text (default) or json lines\n    --resume               Fetch only the symbols an interrupted or partially failed fetch missed\n\nSCREEN OPTIONS:\n    --region <CODE>        Region code declared in assets/configs (default: settings default_region)\n    --preset <NAME>        Preset name under the region filters directory, or a path (default: settings default_preset, else region thresholds)\n    --snapshot <FILE>      Snapshot name under the region snapshots directory, or a path (default: latest)\n    --format <FORMAT>      table (default), csv, or json\n    --sort <METRIC>        curr (default), prevClosed, open, increase, highest, lowest, turnOver, amp, tm\n    --ascending            Sort ascending instead of descending\n\nHISTORY OPTIONS:\n    --region <CODE>        Region code declared in assets/configs (default: settings default_region)\n    --symbol <CODE>        Symbol to download (repeatable)\n    --symbols-file <FILE>  File with one symbol per line (first CSV column)\n    --interval <INTERVAL>  1m, 5m, 30m, day (default), week, or month; must be declared by the region\n    --aggregate <PERIOD>   Roll candles up into week or month bars before export\n    --limit <N>            Number of most recent candles (default: the interval's limit)\n    --format <FORMAT>      csv (default) or json\n    -o, --output <FILE>    Write to a file instead of stdout\n\nHISTORY-CACHE OPTIONS:\n    purge                  Delete cached candles\n    rebuild                Delete cached candles and download the full history again\n    --region <CODE>        Region code declared in assets/configs (default: settings default_region)\n    --symbol <CODE>        Symbol to act on (repeatable; default: every cached symbol)\n    --interval <INTERVAL>  Interval to act on (repeatable; default: every declared interval)\n\nDAEMON OPTIONS:\n    --region <CODE>        Region to snapshot each cycle (repeatable; default: settings default_region)\n    --interval <DURATION>  Time between cycles such as 30s, 5m, 1h (default: 5m)\n    --cron <EXPR>          Five-field cron schedule in local time, e.g. \"*/5 9-15 * * 1-5\"\n    --ignore-sessions      Fetch even outside the region's trading_sessions\n\nVALIDATE OPTIONS:\n    --json                 Print a machine-readable report\n\nEnvironment variables:\n    RUST_LOG               Standard env_logger filter string.\n    STOCK_CLI_HOME         Workspace root used when --root is not given."
     );
 }
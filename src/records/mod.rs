@@ -6,12 +6,18 @@ use std::path::{Path, PathBuf};
 use crate::error::{AppError, Context, Result};
 
 use crate::config::{RegionConfig, Threshold};
+use crate::fetch::{FetchCheckpoint, FetchReport};
 use crate::utils::snapshot_timestamp_slug;
 
 pub mod presets;
 pub mod stock_database;
 
-pub use stock_database::{ensure_metric_thresholds, StockDatabase, FILTERABLE_METRICS};
+pub use stock_database::{
+    ensure_metric_thresholds, write_csv_rows, SortField, StockDatabase, FILTERABLE_METRICS,
+};
+
+/// Journal of an in-progress snapshot fetch, kept beside the snapshots it feeds.
+const FETCH_CHECKPOINT_FILE: &str = ".fetch_checkpoint.jsonl";
 
 /// Facade that keeps snapshot and preset persistence isolated from the rest of the app.
 pub struct Records {
@@ -150,6 +156,25 @@ impl Records {
         Ok(path)
     }
 
+    /// Write the fetch report beside its snapshot, e.g. `<stamp>_raw.csv` -> `<stamp>_report.json`.
+    pub fn save_fetch_report(&self, snapshot_path: &Path, report: &FetchReport) -> Result<PathBuf> {
+        let stem = snapshot_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        let stem = stem.strip_suffix("_raw").unwrap_or(stem);
+        let path = snapshot_path.with_file_name(format!("{}_report.json", stem));
+        let contents = serde_json::to_string_pretty(report)?;
+        fs::write(&path, contents)
+            .with_context(|| format!("Failed to write fetch report {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Checkpoint used to resume snapshot fetches for `region_code`.
+    pub fn fetch_checkpoint(&self, region_code: &str) -> FetchCheckpoint {
+        FetchCheckpoint::new(self.snapshots_dir.join(FETCH_CHECKPOINT_FILE), region_code)
+    }
+
     pub fn load_threshold_preset<P: AsRef<Path>>(
         &self,
         path: P,
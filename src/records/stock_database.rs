@@ -1,4 +1,6 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::io;
 use std::path::Path;
 
 use crate::error::{Context, Result};
@@ -33,6 +35,97 @@ pub fn ensure_metric_thresholds(thresholds: &mut HashMap<String, Threshold>) {
     }
 }
 
+/// Numeric columns the snapshot can be ordered by, shared by the results view and headless output.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SortField {
+    LastPrice,
+    PrevClose,
+    OpenPrice,
+    Change,
+    DayHigh,
+    DayLow,
+    Turnover,
+    Amplitude,
+    TotalMarket,
+}
+
+impl SortField {
+    pub const ALL: [SortField; 9] = [
+        SortField::LastPrice,
+        SortField::PrevClose,
+        SortField::OpenPrice,
+        SortField::Change,
+        SortField::DayHigh,
+        SortField::DayLow,
+        SortField::Turnover,
+        SortField::Amplitude,
+        SortField::TotalMarket,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortField::LastPrice => "Last Price",
+            SortField::PrevClose => "Prev Close",
+            SortField::OpenPrice => "Open Price",
+            SortField::Change => "Change (%)",
+            SortField::DayHigh => "Day High",
+            SortField::DayLow => "Day Low",
+            SortField::Turnover => "Turnover",
+            SortField::Amplitude => "Amplitude",
+            SortField::TotalMarket => "Total Market",
+        }
+    }
+
+    /// Metric key matching `FILTERABLE_METRICS` and the snapshot CSV header.
+    pub fn metric_key(self) -> &'static str {
+        match self {
+            SortField::LastPrice => "curr",
+            SortField::PrevClose => "prevClosed",
+            SortField::OpenPrice => "open",
+            SortField::Change => "increase",
+            SortField::DayHigh => "highest",
+            SortField::DayLow => "lowest",
+            SortField::Turnover => "turnOver",
+            SortField::Amplitude => "amp",
+            SortField::TotalMarket => "tm",
+        }
+    }
+
+    /// Resolve a metric key (case-insensitive) into its sort field.
+    pub fn from_metric_key(key: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|field| field.metric_key().eq_ignore_ascii_case(key))
+    }
+
+    pub fn next(self) -> Self {
+        let idx = Self::ALL
+            .iter()
+            .position(|field| *field == self)
+            .unwrap_or(0);
+        let next_idx = (idx + 1) % Self::ALL.len();
+        Self::ALL[next_idx]
+    }
+
+    pub fn compare(self, a: &StockData, b: &StockData) -> Ordering {
+        match self {
+            SortField::LastPrice => cmp_f64(a.curr, b.curr),
+            SortField::PrevClose => cmp_f64(a.prev_closed, b.prev_closed),
+            SortField::OpenPrice => cmp_f64(a.open, b.open),
+            SortField::Change => cmp_f64(a.increase, b.increase),
+            SortField::DayHigh => cmp_f64(a.highest, b.highest),
+            SortField::DayLow => cmp_f64(a.lowest, b.lowest),
+            SortField::Turnover => cmp_f64(a.turn_over, b.turn_over),
+            SortField::Amplitude => cmp_f64(a.amp, b.amp),
+            SortField::TotalMarket => cmp_f64(a.tm, b.tm),
+        }
+    }
+}
+
+fn cmp_f64(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
 /// Minimal container for the in-memory stock snapshot plus persistence helpers.
 pub struct StockDatabase {
     pub data: Vec<StockData>,
@@ -60,6 +153,35 @@ impl StockDatabase {
             .collect()
     }
 
+    /// Resolve `codes` against the snapshot and order them by `field`, breaking ties by code.
+    pub fn sorted_rows(
+        &self,
+        codes: &[String],
+        field: SortField,
+        descending: bool,
+    ) -> Vec<&StockData> {
+        let mut rows: Vec<&StockData> = codes
+            .iter()
+            .filter_map(|code| self.data.iter().find(|s| &s.stock_code == code))
+            .collect();
+
+        rows.sort_by(|a, b| {
+            let primary = field.compare(a, b);
+            let ord = if primary == Ordering::Equal {
+                a.stock_code.cmp(&b.stock_code)
+            } else {
+                primary
+            };
+            if descending {
+                ord.reverse()
+            } else {
+                ord
+            }
+        });
+
+        rows
+    }
+
     pub fn update(&mut self, new_data: Vec<StockData>) {
         self.data = new_data;
         use std::io::{self, Write};
@@ -73,45 +195,30 @@ impl StockDatabase {
         let _ = out.flush();
     }
 
+    /// Replace rows whose code is already present and append the rest, keeping existing order.
+    pub fn merge(&mut self, rows: Vec<StockData>) {
+        let mut positions: HashMap<String, usize> = self
+            .data
+            .iter()
+            .enumerate()
+            .map(|(idx, stock)| (stock.stock_code.clone(), idx))
+            .collect();
+        for row in rows {
+            match positions.get(&row.stock_code) {
+                Some(&idx) => self.data[idx] = row,
+                None => {
+                    positions.insert(row.stock_code.clone(), self.data.len());
+                    self.data.push(row);
+                }
+            }
+        }
+    }
+
     /// Persist the current snapshot to disk so it can be reloaded by the CLI later.
     pub fn save_to_csv<P: AsRef<Path>>(&self, file_path: P) -> Result<()> {
         let path = file_path.as_ref();
-        let mut writer = csv::Writer::from_path(path).context("Failed to create CSV writer")?;
-
-        writer.write_record(&[
-            "market",
-            "stockName",
-            "stockCode",
-            "curr",
-            "prevClosed",
-            "open",
-            "increase",
-            "highest",
-            "lowest",
-            "turnOver",
-            "amp",
-            "tm",
-        ])?;
-
-        for stock in &self.data {
-            writer.write_record(&[
-                &stock.market,
-                &stock.stock_name,
-                &stock.stock_code,
-                &stock.curr.to_string(),
-                &stock.prev_closed.to_string(),
-                &stock.open.to_string(),
-                &stock.increase.to_string(),
-                &stock.highest.to_string(),
-                &stock.lowest.to_string(),
-                &stock.turn_over.to_string(),
-                &stock.amp.to_string(),
-                &stock.tm.to_string(),
-            ])?;
-        }
-
-        writer.flush()?;
-        Ok(())
+        let writer = csv::Writer::from_path(path).context("Failed to create CSV writer")?;
+        write_csv_rows(writer, &self.data)
     }
 
     /// Load a snapshot produced by `save_to_csv` back into memory.
@@ -199,6 +306,47 @@ impl StockDatabase {
     }
 }
 
+/// Write stock rows using the snapshot CSV layout understood by `load_from_csv`.
+pub fn write_csv_rows<'a, W: io::Write>(
+    mut writer: csv::Writer<W>,
+    rows: impl IntoIterator<Item = &'a StockData>,
+) -> Result<()> {
+    writer.write_record([
+        "market",
+        "stockName",
+        "stockCode",
+        "curr",
+        "prevClosed",
+        "open",
+        "increase",
+        "highest",
+        "lowest",
+        "turnOver",
+        "amp",
+        "tm",
+    ])?;
+
+    for stock in rows {
+        writer.write_record([
+            &stock.market,
+            &stock.stock_name,
+            &stock.stock_code,
+            &stock.curr.to_string(),
+            &stock.prev_closed.to_string(),
+            &stock.open.to_string(),
+            &stock.increase.to_string(),
+            &stock.highest.to_string(),
+            &stock.lowest.to_string(),
+            &stock.turn_over.to_string(),
+            &stock.amp.to_string(),
+            &stock.tm.to_string(),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
 fn metric_value(stock: &StockData, metric: &str) -> Option<f64> {
     match metric {
         "curr" => Some(stock.curr),
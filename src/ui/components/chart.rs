@@ -9,10 +9,16 @@ use ratatui::{
         Block, Borders, Paragraph, Wrap,
     },
 };
-use std::{cmp::Ordering, collections::HashMap, sync::mpsc::TryRecvError};
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{HashMap, VecDeque},
+};
 
-use crate::config::RegionConfig;
-use crate::fetch::{spawn_history_fetch, Candle, HistoryReceiver, StockData};
+use crate::config::{HistoryInterval, RegionConfig};
+use crate::fetch::{
+    aggregate_candles, spawn_history_fetch, AggregatePeriod, Candle, HistoryTask, StockData,
+};
 use crate::ui::components::utils::split_vertical;
 
 const TIMEFRAMES: &[(&str, ChronoDuration)] = &[
@@ -23,56 +29,62 @@ const TIMEFRAMES: &[(&str, ChronoDuration)] = &[
     ("1W", ChronoDuration::days(7)),
 ];
 
+/// History downloads kept in flight at once; scrolling past more rows aborts the oldest.
+const MAX_PENDING_HISTORY_FETCHES: usize = 3;
+
+/// Share of the chart height given to the volume panel, and the smallest chart it is drawn in.
+const VOLUME_PANEL_PERCENT: u16 = 25;
+const VOLUME_PANEL_MIN_CHART_HEIGHT: u16 = 16;
+
 const BODY_EPSILON: f64 = 1e-4;
 const DATE_LABEL_FMT: &str = "%Y-%m-%d";
 const DATE_LABEL_FMT_SHORT: &str = "%m-%d";
 const DATE_LABEL_FMT_MEDIUM: &str = "%Y-%m";
+const TIMESTAMP_LABEL_FMT: &str = "%Y-%m-%d %H:%M";
+const TIME_LABEL_FMT: &str = "%m-%d %H:%M";
+const TIME_LABEL_FMT_SHORT: &str = "%H:%M";
 
 /// Tracks chart state and caches per-stock historical data.
 #[derive(Default)]
 pub struct ChartState {
     pub show: bool,
     pub timeframe_index: usize,
+    /// Candle granularity, cycled independently of the timeframe window.
+    interval: HistoryInterval,
+    /// Intervals the active region declares, finest first.
+    intervals: Vec<HistoryInterval>,
+    /// Calendar period the loaded candles are rolled up into for display.
+    aggregation: Option<AggregatePeriod>,
     active_key: Option<String>,
     history_cache: HashMap<String, Vec<Candle>>,
-    pending_fetches: HashMap<String, HistoryReceiver>,
+    /// In-flight downloads, oldest first.
+    pending_fetches: VecDeque<(String, HistoryTask)>,
     last_error: Option<String>,
 }
 
 impl ChartState {
     pub fn prepare_history(&mut self, region: &RegionConfig, stock_code: &str) {
+        self.intervals = region.provider.history_intervals();
+        if !self.intervals.contains(&self.interval) {
+            self.interval = region.provider.history().interval;
+        }
         let market = region.code.as_str();
-        let key = cache_key(market, stock_code);
+        let key = cache_key(market, self.interval, stock_code);
         if self.active_key.as_deref() != Some(key.as_str()) {
             self.active_key = Some(key.clone());
             self.last_error = None;
         }
 
+        self.collect_finished();
         if self.history_cache.contains_key(key.as_str()) {
             return;
         }
 
-        if let Some(outcome) = self
+        if self
             .pending_fetches
-            .get(key.as_str())
-            .map(|rx| rx.try_recv())
+            .iter()
+            .any(|(pending, _)| *pending == key)
         {
-            match outcome {
-                Ok(Ok(history)) => {
-                    self.history_cache.insert(key.clone(), history);
-                    self.pending_fetches.remove(key.as_str());
-                    self.last_error = None;
-                }
-                Ok(Err(err)) => {
-                    self.pending_fetches.remove(key.as_str());
-                    self.last_error = Some(err.to_string());
-                }
-                Err(TryRecvError::Empty) => {}
-                Err(TryRecvError::Disconnected) => {
-                    self.pending_fetches.remove(key.as_str());
-                    self.last_error = Some("History fetch task ended unexpectedly".to_string());
-                }
-            }
             return;
         }
 
@@ -84,18 +96,62 @@ impl ChartState {
         {
             return;
         }
-        let rx = spawn_history_fetch(stock_code, region);
-        self.pending_fetches.insert(key, rx);
+
+        let task = spawn_history_fetch(stock_code, region, self.interval);
+        self.track_fetch(key, task);
+    }
+
+    /// Queue a download, aborting the oldest ones so at most `MAX_PENDING_HISTORY_FETCHES` run.
+    fn track_fetch(&mut self, key: String, task: HistoryTask) {
+        while self.pending_fetches.len() >= MAX_PENDING_HISTORY_FETCHES {
+            if let Some((_, task)) = self.pending_fetches.pop_front() {
+                task.abort();
+            }
+        }
+        self.pending_fetches.push_back((key, task));
         self.last_error = None;
     }
 
+    /// Move finished downloads into the cache; only the active row's failure is surfaced.
+    fn collect_finished(&mut self) {
+        let mut idx = 0;
+        while idx < self.pending_fetches.len() {
+            let (key, task) = &mut self.pending_fetches[idx];
+            let Some(outcome) = task.try_result() else {
+                idx += 1;
+                continue;
+            };
+
+            let is_active = self.active_key.as_deref() == Some(key.as_str());
+            match outcome {
+                Ok(history) => {
+                    self.history_cache.insert(key.clone(), history);
+                    if is_active {
+                        self.last_error = None;
+                    }
+                }
+                Err(err) if is_active => self.last_error = Some(err.to_string()),
+                Err(_) => {}
+            }
+            self.pending_fetches.remove(idx);
+        }
+    }
+
+    /// Abort every in-flight history download.
+    pub fn cancel_pending(&mut self) {
+        for (_, task) in self.pending_fetches.drain(..) {
+            task.abort();
+        }
+    }
+
     pub fn history_for(&self, market: &str, stock_code: &str) -> Option<&Vec<Candle>> {
-        let key = cache_key(market, stock_code);
+        let key = cache_key(market, self.interval, stock_code);
         self.history_cache.get(&key)
     }
 
     pub fn hide(&mut self) {
         self.show = false;
+        self.cancel_pending();
         self.last_error = None;
         self.active_key = None;
     }
@@ -113,8 +169,50 @@ impl ChartState {
         self.timeframe_index = (self.timeframe_index + TIMEFRAMES.len() - 1) % TIMEFRAMES.len();
     }
 
+    pub fn interval(&self) -> HistoryInterval {
+        self.interval
+    }
+
+    /// Switch to the next declared interval; the next `prepare_history` loads it.
+    pub fn next_interval(&mut self) {
+        self.step_interval(1);
+    }
+
+    pub fn prev_interval(&mut self) {
+        self.step_interval(self.intervals.len().saturating_sub(1));
+    }
+
+    fn step_interval(&mut self, step: usize) {
+        if self.intervals.is_empty() {
+            return;
+        }
+        let current = self
+            .intervals
+            .iter()
+            .position(|interval| *interval == self.interval)
+            .unwrap_or(0);
+        self.interval = self.intervals[(current + step) % self.intervals.len()];
+    }
+
+    /// Aggregation applied to the current interval; periods no coarser than it are skipped.
+    pub fn aggregation(&self) -> Option<AggregatePeriod> {
+        self.aggregation.filter(|period| match period {
+            AggregatePeriod::Week => self.interval < HistoryInterval::Week,
+            AggregatePeriod::Month => self.interval < HistoryInterval::Month,
+        })
+    }
+
+    /// Cycle the display aggregation: off, week, month.
+    pub fn cycle_aggregation(&mut self) {
+        self.aggregation = match self.aggregation {
+            None => Some(AggregatePeriod::Week),
+            Some(AggregatePeriod::Week) => Some(AggregatePeriod::Month),
+            Some(AggregatePeriod::Month) => None,
+        };
+    }
+
     pub fn last_error(&self, market: &str, stock_code: &str) -> Option<&str> {
-        let key = cache_key(market, stock_code);
+        let key = cache_key(market, self.interval, stock_code);
         if self.active_key.as_deref() == Some(key.as_str()) {
             self.last_error.as_deref()
         } else {
@@ -123,8 +221,8 @@ impl ChartState {
     }
 }
 
-fn cache_key(market: &str, stock_code: &str) -> String {
-    format!("{}:{}", market, stock_code)
+fn cache_key(market: &str, interval: HistoryInterval, stock_code: &str) -> String {
+    format!("{}:{}:{}", market, interval.label(), stock_code)
 }
 
 pub fn render_chart_panel(
@@ -155,7 +253,19 @@ pub fn render_chart_panel(
     if let Some(stock) = stock {
         if let Some(history) = chart.history_for(&stock.market, &stock.stock_code) {
             let (label, duration) = TIMEFRAMES[chart.timeframe_index];
-            let filtered = filter_history(history, duration);
+            let interval = chart.interval();
+            let aggregation = chart.aggregation();
+            let bar_label = aggregation.map_or(interval.label(), AggregatePeriod::label);
+            let stamp_format = if interval.is_intraday() && aggregation.is_none() {
+                TIMESTAMP_LABEL_FMT
+            } else {
+                DATE_LABEL_FMT
+            };
+            let source: Cow<'_, [Candle]> = match aggregation {
+                Some(period) => Cow::Owned(aggregate_candles(history, period)),
+                None => Cow::Borrowed(history),
+            };
+            let filtered = filter_history(&source, duration);
             if filtered.is_empty() {
                 f.render_widget(
                     Paragraph::new("No historical data returned for this stock.")
@@ -163,7 +273,7 @@ pub fn render_chart_panel(
                         .block(
                             Block::default()
                                 .borders(Borders::ALL)
-                                .title(format!("{} — {}", stock.stock_code, label)),
+                                .title(format!("{} — {} {}", stock.stock_code, bar_label, label)),
                         ),
                     chart_area,
                 );
@@ -180,6 +290,22 @@ pub fn render_chart_panel(
                     })
                     .collect::<Vec<_>>()
                     .join("  ");
+                let interval_legend = chart
+                    .intervals
+                    .iter()
+                    .map(|candidate| {
+                        if *candidate == interval {
+                            format!("[{}]", candidate.label())
+                        } else {
+                            candidate.label().to_string()
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let aggregation_legend = match aggregation {
+                    Some(period) => format!(" → {}", period.label()),
+                    None => String::new(),
+                };
 
                 let first = filtered.first().unwrap();
                 let last = filtered.last().unwrap();
@@ -202,9 +328,23 @@ pub fn render_chart_panel(
                         compressed
                     }
                 };
+                let has_volume = draw_series.iter().any(|candle| candle.volume.is_some());
+                let (price_area, volume_area) =
+                    if has_volume && chart_area.height >= VOLUME_PANEL_MIN_CHART_HEIGHT {
+                        let parts = split_vertical(
+                            chart_area,
+                            &[
+                                Constraint::Percentage(100 - VOLUME_PANEL_PERCENT),
+                                Constraint::Percentage(VOLUME_PANEL_PERCENT),
+                            ],
+                        );
+                        (parts[0], Some(parts[1]))
+                    } else {
+                        (chart_area, None)
+                    };
                 let series_len = draw_series.len().max(1);
                 let width_px = chart_area.width.max(1) as f64;
-                let height_px = chart_area.height.max(1) as f64;
+                let height_px = price_area.height.max(1) as f64;
 
                 let left_margin = 7.0;
                 let right_margin = 1.0;
@@ -224,7 +364,7 @@ pub fn render_chart_panel(
                     available_width
                 };
                 let half_body = (base_width * 0.35).clamp(0.03, 0.3);
-                let half_wick = half_body.min(0.2).max(0.03);
+                let half_wick = half_body.clamp(0.03, 0.2);
                 let axis_x_end = axis_x + available_width;
                 let axis_tick_length_x = 5.0;
 
@@ -257,10 +397,11 @@ pub fn render_chart_panel(
                         acc
                     });
 
-                let date_tick_positions = compute_date_ticks(&draw_series, 7)
-                    .into_iter()
-                    .map(|(idx, label)| (axis_x + idx as f64 * x_scale, label))
-                    .collect::<Vec<_>>();
+                let date_tick_positions =
+                    compute_date_ticks(&draw_series, 7, stamp_format == TIMESTAMP_LABEL_FMT)
+                        .into_iter()
+                        .map(|(idx, label)| (axis_x + idx as f64 * x_scale, label))
+                        .collect::<Vec<_>>();
 
                 let mut highest = &filtered[0];
                 let mut lowest = &filtered[0];
@@ -283,13 +424,24 @@ pub fn render_chart_panel(
                     }
                 }
 
-                let price_tick_values = price_tick_values;
-                let date_tick_positions = date_tick_positions;
+                if let Some(volume_area) = volume_area {
+                    render_volume_panel(
+                        f,
+                        volume_area,
+                        &draw_series,
+                        VolumeLayout {
+                            axis_x,
+                            x_scale,
+                            half_body,
+                        },
+                    );
+                }
+
                 let candles = draw_series.clone();
                 let canvas = Canvas::default()
                     .block(Block::default().borders(Borders::ALL).title(format!(
-                        "{} — {} | {}  (←/→/h/l cycle • X close)",
-                        stock.stock_code, label, timeframe_legend
+                        "{} — {} | {} | {}{}  (←/→/h/l window • g/G interval • a aggregate • X close)",
+                        stock.stock_code, label, timeframe_legend, interval_legend, aggregation_legend
                     )))
                     .marker(Marker::HalfBlock)
                     .x_bounds(x_bounds)
@@ -366,19 +518,23 @@ pub fn render_chart_panel(
                         }
                     });
 
-                f.render_widget(canvas, chart_area);
+                f.render_widget(canvas, price_area);
 
                 help_text = format!(
-                    "{} • {} sessions • {} -> {} • High {:.2} on {} • Low {:.2} on {}",
+                    "{} • {} {} candles • {} -> {} • High {:.2} on {} • Low {:.2} on {}",
                     label,
                     filtered.len(),
-                    first.timestamp.format("%Y-%m-%d"),
-                    last.timestamp.format("%Y-%m-%d"),
+                    bar_label,
+                    first.timestamp.format(stamp_format),
+                    last.timestamp.format(stamp_format),
                     highest.high,
-                    highest.timestamp.format("%Y-%m-%d"),
+                    highest.timestamp.format(stamp_format),
                     lowest.low,
-                    lowest.timestamp.format("%Y-%m-%d"),
+                    lowest.timestamp.format(stamp_format),
                 );
+                if let Some(volume) = last.volume {
+                    help_text.push_str(&format!(" • Vol {}", format_volume(volume)));
+                }
             }
         } else if let Some(message) = chart.last_error(&stock.market, &stock.stock_code) {
             f.render_widget(
@@ -418,6 +574,92 @@ pub fn render_chart_panel(
     );
 }
 
+/// Horizontal geometry shared with the candlestick canvas so volume bars line up with candles.
+struct VolumeLayout {
+    axis_x: f64,
+    x_scale: f64,
+    half_body: f64,
+}
+
+fn render_volume_panel(f: &mut Frame<'_>, area: Rect, candles: &[Candle], layout: VolumeLayout) {
+    let max_volume = candles
+        .iter()
+        .filter_map(|candle| candle.volume)
+        .fold(0.0_f64, f64::max);
+    let width_px = area.width.max(1) as f64;
+    let height_px = area.height.max(1) as f64;
+    let available_height = (height_px - 1.0).max(1.0);
+    let volume_scale = if max_volume > 0.0 {
+        available_height / max_volume
+    } else {
+        0.0
+    };
+
+    let bars = candles
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, candle)| {
+            let volume = candle.volume?;
+            let color = if candle.close >= candle.open {
+                Color::Green
+            } else {
+                Color::Red
+            };
+            Some((
+                layout.axis_x + idx as f64 * layout.x_scale,
+                volume * volume_scale,
+                color,
+            ))
+        })
+        .collect::<Vec<_>>();
+    let half_body = layout.half_body;
+    let axis_x = layout.axis_x;
+
+    let canvas = Canvas::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Volume (max {})", format_volume(max_volume))),
+        )
+        .marker(Marker::HalfBlock)
+        .x_bounds([0.0, width_px])
+        .y_bounds([0.0, height_px])
+        .paint(move |ctx| {
+            for (x, height, color) in bars.iter() {
+                if *height <= 0.0 {
+                    continue;
+                }
+                ctx.draw(&Rectangle {
+                    x: x - half_body,
+                    y: 0.0,
+                    width: half_body * 2.0,
+                    height: *height,
+                    color: *color,
+                });
+            }
+            ctx.layer();
+            ctx.draw(&CanvasLine {
+                x1: axis_x,
+                y1: 0.0,
+                x2: axis_x,
+                y2: available_height,
+                color: Color::DarkGray,
+            });
+        });
+
+    f.render_widget(canvas, area);
+}
+
+/// Compact volume label such as `1.25M` or `830.4K`.
+fn format_volume(volume: f64) -> String {
+    const UNITS: [(f64, &str); 3] = [(1e9, "B"), (1e6, "M"), (1e3, "K")];
+    UNITS
+        .iter()
+        .find(|(scale, _)| volume.abs() >= *scale)
+        .map(|(scale, unit)| format!("{:.2}{}", volume / scale, unit))
+        .unwrap_or_else(|| format!("{:.0}", volume))
+}
+
 fn filter_history(history: &[Candle], duration: ChronoDuration) -> Vec<Candle> {
     let cutoff = Local::now() - duration;
     let filtered: Vec<Candle> = history
@@ -439,7 +681,7 @@ fn compress_to_width(candles: &[Candle], width: u16) -> Vec<Candle> {
         return candles.to_vec();
     }
 
-    let stride = (candles.len() + max_points - 1) / max_points;
+    let stride = candles.len().div_ceil(max_points);
     let mut reduced = Vec::with_capacity(max_points);
 
     for chunk in candles.chunks(stride) {
@@ -454,6 +696,8 @@ fn compress_to_width(candles: &[Candle], width: u16) -> Vec<Candle> {
             .map(|c| c.high)
             .fold(f64::NEG_INFINITY, f64::max);
         aggregated.low = chunk.iter().map(|c| c.low).fold(f64::INFINITY, f64::min);
+        aggregated.volume = sum_present(chunk.iter().map(|c| c.volume));
+        aggregated.amount = sum_present(chunk.iter().map(|c| c.amount));
         aggregated.timestamp = chunk.last().unwrap().timestamp;
         reduced.push(aggregated);
     }
@@ -465,6 +709,13 @@ fn compress_to_width(candles: &[Candle], width: u16) -> Vec<Candle> {
     reduced
 }
 
+/// Sum of the values that are present; `None` when none are.
+fn sum_present(values: impl Iterator<Item = Option<f64>>) -> Option<f64> {
+    values
+        .flatten()
+        .fold(None, |total, value| Some(total.unwrap_or(0.0) + value))
+}
+
 fn compute_price_ticks(min: f64, max: f64, desired: usize) -> Vec<f64> {
     let desired = desired.max(2);
     if !min.is_finite() || !max.is_finite() {
@@ -490,14 +741,19 @@ fn compute_price_ticks(min: f64, max: f64, desired: usize) -> Vec<f64> {
         .collect()
 }
 
-fn compute_date_ticks(candles: &[Candle], desired: usize) -> Vec<(usize, String)> {
+fn compute_date_ticks(candles: &[Candle], desired: usize, intraday: bool) -> Vec<(usize, String)> {
     if candles.is_empty() {
         return Vec::new();
     }
 
+    let end_format = if intraday {
+        TIME_LABEL_FMT
+    } else {
+        DATE_LABEL_FMT
+    };
     let last_index = candles.len() - 1;
     if last_index == 0 {
-        return vec![(0, candles[0].timestamp.format(DATE_LABEL_FMT).to_string())];
+        return vec![(0, candles[0].timestamp.format(end_format).to_string())];
     }
 
     let desired = desired.max(2).min(candles.len());
@@ -515,7 +771,13 @@ fn compute_date_ticks(candles: &[Candle], desired: usize) -> Vec<(usize, String)
     let total_days = (last_ts.date_naive() - first_ts.date_naive())
         .num_days()
         .abs();
-    let mid_format = if total_days > 365 {
+    let mid_format = if intraday {
+        if total_days < 1 {
+            TIME_LABEL_FMT_SHORT
+        } else {
+            TIME_LABEL_FMT
+        }
+    } else if total_days > 365 {
         DATE_LABEL_FMT_MEDIUM
     } else {
         DATE_LABEL_FMT_SHORT
@@ -526,7 +788,7 @@ fn compute_date_ticks(candles: &[Candle], desired: usize) -> Vec<(usize, String)
         .map(|idx| {
             let ts = candles[idx].timestamp;
             let label = if idx == 0 || idx == last_index {
-                ts.format(DATE_LABEL_FMT).to_string()
+                ts.format(end_format).to_string()
             } else {
                 ts.format(mid_format).to_string()
             };
@@ -534,3 +796,148 @@ fn compute_date_ticks(candles: &[Candle], desired: usize) -> Vec<(usize, String)
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::error::AppError;
+
+    /// Sets its flag when dropped, which is how an aborted download's future goes away.
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, AtomicOrdering::SeqCst);
+        }
+    }
+
+    /// A download that never finishes, plus a flag raised once it is aborted.
+    fn stalled_fetch() -> (HistoryTask, Arc<AtomicBool>) {
+        let aborted = Arc::new(AtomicBool::new(false));
+        let flag = DropFlag(aborted.clone());
+        let task = HistoryTask::spawn(async move {
+            let _flag = flag;
+            std::future::pending().await
+        });
+        (task, aborted)
+    }
+
+    async fn settle() {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    fn pending_keys(state: &ChartState) -> Vec<&str> {
+        state
+            .pending_fetches
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn oldest_fetches_are_aborted_when_the_queue_is_full() {
+        let mut state = ChartState::default();
+        let mut flags = Vec::new();
+        for code in ["600000", "600001", "600002", "600003", "600004"] {
+            let (task, aborted) = stalled_fetch();
+            state.track_fetch(code.to_string(), task);
+            flags.push(aborted);
+        }
+        settle().await;
+
+        assert_eq!(pending_keys(&state), ["600002", "600003", "600004"]);
+        let aborted: Vec<bool> = flags
+            .iter()
+            .map(|flag| flag.load(AtomicOrdering::SeqCst))
+            .collect();
+        assert_eq!(aborted, [true, true, false, false, false]);
+    }
+
+    #[tokio::test]
+    async fn hiding_the_chart_aborts_every_pending_fetch() {
+        let mut state = ChartState {
+            show: true,
+            ..ChartState::default()
+        };
+        let (first, first_aborted) = stalled_fetch();
+        let (second, second_aborted) = stalled_fetch();
+        state.track_fetch("600000".to_string(), first);
+        state.track_fetch("600001".to_string(), second);
+
+        state.hide();
+        settle().await;
+
+        assert!(!state.show);
+        assert!(state.pending_fetches.is_empty());
+        assert!(first_aborted.load(AtomicOrdering::SeqCst));
+        assert!(second_aborted.load(AtomicOrdering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn only_the_active_row_surfaces_a_failed_fetch() {
+        let mut state = ChartState {
+            active_key: Some("CN:day:600001".to_string()),
+            ..ChartState::default()
+        };
+        state.track_fetch(
+            "CN:day:600000".to_string(),
+            HistoryTask::spawn(async { Err(AppError::message("stale row failed")) }),
+        );
+        state.track_fetch(
+            "CN:day:600001".to_string(),
+            HistoryTask::spawn(async { Err(AppError::message("active row failed")) }),
+        );
+        state.track_fetch(
+            "CN:day:600002".to_string(),
+            HistoryTask::spawn(async { Ok(Vec::new()) }),
+        );
+        settle().await;
+
+        state.collect_finished();
+        assert!(state.pending_fetches.is_empty());
+        assert_eq!(state.last_error("CN", "600001"), Some("active row failed"));
+        assert!(state.history_for("CN", "600002").is_some());
+        assert!(state.history_for("CN", "600000").is_none());
+    }
+
+    #[test]
+    fn intervals_cycle_through_the_declared_ones() {
+        let mut state = ChartState {
+            intervals: vec![
+                HistoryInterval::Minute5,
+                HistoryInterval::Day,
+                HistoryInterval::Week,
+            ],
+            ..ChartState::default()
+        };
+
+        state.next_interval();
+        assert_eq!(state.interval(), HistoryInterval::Week);
+        state.next_interval();
+        assert_eq!(state.interval(), HistoryInterval::Minute5);
+        state.prev_interval();
+        assert_eq!(state.interval(), HistoryInterval::Week);
+    }
+
+    #[test]
+    fn aggregation_skips_periods_no_finer_than_the_interval() {
+        let mut state = ChartState {
+            intervals: vec![HistoryInterval::Day, HistoryInterval::Week],
+            ..ChartState::default()
+        };
+
+        state.cycle_aggregation();
+        assert_eq!(state.aggregation(), Some(AggregatePeriod::Week));
+        state.next_interval();
+        assert_eq!(state.aggregation(), None);
+
+        state.cycle_aggregation();
+        assert_eq!(state.aggregation(), Some(AggregatePeriod::Month));
+        state.cycle_aggregation();
+        assert_eq!(state.aggregation(), None);
+    }
+}
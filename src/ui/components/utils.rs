@@ -23,7 +23,7 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 pub fn split_vertical(area: Rect, constraints: &[Constraint]) -> Vec<Rect> {
     Layout::default()
         .direction(Direction::Vertical)
-        .constraints(constraints.iter().copied().collect::<Vec<_>>())
+        .constraints(constraints.to_vec())
         .split(area)
         .to_vec()
 }
@@ -31,7 +31,7 @@ pub fn split_vertical(area: Rect, constraints: &[Constraint]) -> Vec<Rect> {
 pub fn split_horizontal(area: Rect, constraints: &[Constraint]) -> Vec<Rect> {
     Layout::default()
         .direction(Direction::Horizontal)
-        .constraints(constraints.iter().copied().collect::<Vec<_>>())
+        .constraints(constraints.to_vec())
         .split(area)
         .to_vec()
 }
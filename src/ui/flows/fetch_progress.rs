@@ -1,6 +1,6 @@
-use crate::config::RegionConfig;
 use crate::error::{AppError, Result};
-use crate::fetch::{SnapshotFetcher, StockData};
+use crate::fetch::rate_limit::throttle_states;
+use crate::fetch::{FetchedSnapshot, SnapshotFetcher};
 use crate::ui::styles::{header_text, secondary_line};
 use crate::ui::{
     components::utils::{centered_rect, split_vertical},
@@ -9,12 +9,12 @@ use crate::ui::{
 use crossterm::event::{self, Event, KeyCode, KeyModifiers};
 use ratatui::{prelude::*, widgets::*};
 
-pub async fn run_fetch_progress(
-    stock_codes: &[String],
-    region_config: RegionConfig,
-    static_names: std::collections::HashMap<String, String>,
-) -> Result<Vec<StockData>> {
-    let fetcher = SnapshotFetcher::new(stock_codes.to_vec(), region_config, static_names);
+/// Symbols listed per failure cause on the completion screen.
+const REPORT_SYMBOLS_SHOWN: usize = 3;
+
+/// Drive `fetcher` behind a progress screen; Esc aborts and returns `AppError::Cancelled`.
+pub async fn run_fetch_progress(fetcher: SnapshotFetcher) -> Result<FetchedSnapshot> {
+    let rate_limited = fetcher.region_config.provider.rate_limit().is_some();
     let progress = fetcher.progress_counter.clone();
     let total = fetcher.total_stocks;
     let handle = tokio::spawn(async move { fetcher.fetch_data().await });
@@ -30,6 +30,7 @@ pub async fn run_fetch_progress(
         } else {
             (done as f64 / total as f64).clamp(0.0, 1.0)
         };
+        let throttle = throttle_label(rate_limited);
 
         guard.terminal_mut().draw(|f| {
             let size = f.size();
@@ -46,6 +47,7 @@ pub async fn run_fetch_progress(
                     Constraint::Length(1),
                     Constraint::Length(1),
                     Constraint::Length(1),
+                    Constraint::Length(1),
                 ],
             );
             let label = format!(
@@ -64,9 +66,13 @@ pub async fn run_fetch_progress(
                 chunks[1],
             );
             f.render_widget(
-                Paragraph::new(secondary_line("Esc to cancel")).alignment(Alignment::Center),
+                Paragraph::new(secondary_line(throttle.as_str())).alignment(Alignment::Center),
                 chunks[2],
             );
+            f.render_widget(
+                Paragraph::new(secondary_line("Esc to cancel")).alignment(Alignment::Center),
+                chunks[3],
+            );
         })?;
 
         if handle.is_finished() {
@@ -95,19 +101,38 @@ pub async fn run_fetch_progress(
     }
 
     let res = handle.await?;
-    let data = res?;
+    let snapshot = res?;
+    let report = &snapshot.report;
+    let mut text = header_text(format!(
+        "Fetched {} of {} records.",
+        snapshot.data.len(),
+        report.total
+    ));
+    if report.failed_count() > 0 {
+        text.lines.push(Line::from(format!(
+            "{} failed: {}",
+            report.failed_count(),
+            report.failure_summary()
+        )));
+        text.lines.extend(
+            report
+                .failure_lines(REPORT_SYMBOLS_SHOWN)
+                .into_iter()
+                .map(secondary_line),
+        );
+    }
+    text.lines.push(Line::from("Press Enter to continue."));
+    let height_percent = if report.failed_count() > 0 { 40 } else { 20 };
     guard.terminal_mut().draw(|f| {
         let size = f.size();
-        let area = centered_rect(60, 20, size);
+        let area = centered_rect(70, height_percent, size);
         f.render_widget(Clear, area);
         let block = Block::default().borders(Borders::ALL).title("Done");
         f.render_widget(block.clone(), area);
         let inner = block.inner(area);
-        let msg = Paragraph::new(header_text(format!(
-            "Fetched {} records.\nPress Enter to continue.",
-            data.len()
-        )))
-        .alignment(Alignment::Center);
+        let msg = Paragraph::new(text.clone())
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
         f.render_widget(msg, inner);
     })?;
     loop {
@@ -129,5 +154,33 @@ pub async fn run_fetch_progress(
     }
 
     guard.restore()?;
-    Ok(data)
+    Ok(snapshot)
+}
+
+/// One-line summary of the provider rate limiters for the progress screen.
+fn throttle_label(rate_limited: bool) -> String {
+    if !rate_limited {
+        return "Rate limit: off".to_string();
+    }
+
+    let states = throttle_states();
+    if states.is_empty() {
+        return "Rate limit: waiting for first request".to_string();
+    }
+
+    states
+        .iter()
+        .map(|state| {
+            let status = if state.waiting > 0 {
+                format!("throttling, {} queued", state.waiting)
+            } else {
+                format!("{:.0}/{} tokens", state.available.floor(), state.burst)
+            };
+            format!(
+                "{} {}/s: {} ({} delayed)",
+                state.host, state.requests_per_second, status, state.throttled
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
 }
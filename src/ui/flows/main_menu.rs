@@ -13,6 +13,7 @@ pub fn run_main_menu(
     allow_region_switch: bool,
     region_code: &str,
     region_name: &str,
+    resume_hint: Option<&str>,
 ) -> Result<MenuAction> {
     // Ensure raw mode and the alternate screen are always restored regardless of how we exit.
     let mut guard = TerminalGuard::new()?;
@@ -28,6 +29,13 @@ pub fn run_main_menu(
             "Fetch the newest stock snapshot",
             MenuAction::Update,
         ),
+    ];
+
+    if let Some(hint) = resume_hint {
+        items.push(("Resume Fetch", hint, MenuAction::Resume));
+    }
+
+    items.extend([
         (
             "Filters",
             "Adjust, save, or load threshold presets",
@@ -38,7 +46,7 @@ pub fn run_main_menu(
             "Pick a saved dataset from assets/snapshots/",
             MenuAction::Load,
         ),
-    ];
+    ]);
 
     if allow_region_switch {
         items.push((
@@ -108,8 +116,8 @@ pub fn run_main_menu(
         })?;
 
         if event::poll(Duration::from_millis(200))? {
-            match event::read()? {
-                Event::Key(k) => match k.code {
+            if let Event::Key(k) = event::read()? {
+                match k.code {
                     KeyCode::Up | KeyCode::Char('k') => {
                         // Wrap-around navigation keeps the UI snappy for keyboard users.
                         if selected == 0 {
@@ -123,7 +131,7 @@ pub fn run_main_menu(
                     }
                     KeyCode::Enter => {
                         // Leave the alternate screen before returning so the caller can print freely.
-                        let action = items[selected].2.clone();
+                        let action = items[selected].2;
                         guard.restore()?;
                         return Ok(action);
                     }
@@ -136,8 +144,7 @@ pub fn run_main_menu(
                         return Ok(MenuAction::Exit);
                     }
                     _ => {}
-                },
-                _ => {}
+                }
             }
         }
     }
@@ -208,8 +215,8 @@ pub fn run_filters_menu() -> Result<FilterMenuAction> {
         })?;
 
         if event::poll(Duration::from_millis(200))? {
-            match event::read()? {
-                Event::Key(k) => match k.code {
+            if let Event::Key(k) = event::read()? {
+                match k.code {
                     KeyCode::Up | KeyCode::Char('k') => {
                         if selected == 0 {
                             selected = entries.len() - 1;
@@ -221,7 +228,7 @@ pub fn run_filters_menu() -> Result<FilterMenuAction> {
                         selected = (selected + 1) % entries.len();
                     }
                     KeyCode::Enter => {
-                        let action = entries[selected].2.clone();
+                        let action = entries[selected].2;
                         guard.restore()?;
                         return Ok(action);
                     }
@@ -234,8 +241,7 @@ pub fn run_filters_menu() -> Result<FilterMenuAction> {
                         return Ok(FilterMenuAction::Back);
                     }
                     _ => {}
-                },
-                _ => {}
+                }
             }
         }
     }
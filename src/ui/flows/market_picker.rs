@@ -60,8 +60,8 @@ pub fn run_market_picker(options: &[(String, String)]) -> Result<String> {
         })?;
 
         if event::poll(Duration::from_millis(150))? {
-            match event::read()? {
-                Event::Key(key) => match key.code {
+            if let Event::Key(key) = event::read()? {
+                match key.code {
                     KeyCode::Up | KeyCode::Char('k') => {
                         if selected == 0 {
                             selected = options.len() - 1;
@@ -86,8 +86,7 @@ pub fn run_market_picker(options: &[(String, String)]) -> Result<String> {
                         return Err(AppError::Cancelled);
                     }
                     _ => {}
-                },
-                _ => {}
+                }
             }
         }
     }
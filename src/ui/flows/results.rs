@@ -6,7 +6,7 @@ use std::{convert::TryFrom, time::Duration};
 use unicode_width::UnicodeWidthStr;
 
 use crate::fetch::StockData;
-use crate::records::StockDatabase;
+use crate::records::{SortField, StockDatabase};
 use crate::ui::styles::{secondary_line, ACCENT};
 use crate::ui::{
     components::{
@@ -18,70 +18,6 @@ use crate::ui::{
     TerminalGuard, UiRoute,
 };
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-enum SortField {
-    LastPrice,
-    PrevClose,
-    OpenPrice,
-    Change,
-    DayHigh,
-    DayLow,
-    Turnover,
-    Amplitude,
-    TotalMarket,
-}
-
-impl SortField {
-    const ALL: [SortField; 9] = [
-        SortField::LastPrice,
-        SortField::PrevClose,
-        SortField::OpenPrice,
-        SortField::Change,
-        SortField::DayHigh,
-        SortField::DayLow,
-        SortField::Turnover,
-        SortField::Amplitude,
-        SortField::TotalMarket,
-    ];
-
-    fn label(self) -> &'static str {
-        match self {
-            SortField::LastPrice => "Last Price",
-            SortField::PrevClose => "Prev Close",
-            SortField::OpenPrice => "Open Price",
-            SortField::Change => "Change (%)",
-            SortField::DayHigh => "Day High",
-            SortField::DayLow => "Day Low",
-            SortField::Turnover => "Turnover",
-            SortField::Amplitude => "Amplitude",
-            SortField::TotalMarket => "Total Market",
-        }
-    }
-
-    fn next(self) -> Self {
-        let idx = Self::ALL
-            .iter()
-            .position(|field| *field == self)
-            .unwrap_or(0);
-        let next_idx = (idx + 1) % Self::ALL.len();
-        Self::ALL[next_idx]
-    }
-
-    fn compare(self, a: &StockData, b: &StockData) -> std::cmp::Ordering {
-        match self {
-            SortField::LastPrice => cmp_f64(a.curr, b.curr),
-            SortField::PrevClose => cmp_f64(a.prev_closed, b.prev_closed),
-            SortField::OpenPrice => cmp_f64(a.open, b.open),
-            SortField::Change => cmp_f64(a.increase, b.increase),
-            SortField::DayHigh => cmp_f64(a.highest, b.highest),
-            SortField::DayLow => cmp_f64(a.lowest, b.lowest),
-            SortField::Turnover => cmp_f64(a.turn_over, b.turn_over),
-            SortField::Amplitude => cmp_f64(a.amp, b.amp),
-            SortField::TotalMarket => cmp_f64(a.tm, b.tm),
-        }
-    }
-}
-
 #[derive(Clone, Copy, Debug)]
 struct SortState {
     field: SortField,
@@ -113,39 +49,15 @@ impl SortState {
     }
 }
 
-fn cmp_f64(a: f64, b: f64) -> std::cmp::Ordering {
-    a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
-}
-
 fn build_sorted_rows<'a>(
     database: &'a StockDatabase,
     codes: &[String],
     sort: SortState,
 ) -> Vec<&'a StockData> {
-    let mut rows: Vec<&StockData> = codes
-        .iter()
-        .filter_map(|code| database.data.iter().find(|s| &s.stock_code == code))
-        .collect();
-
-    rows.sort_by(|a, b| {
-        use std::cmp::Ordering;
-
-        let primary = sort.field.compare(a, b);
-        let ord = if primary == Ordering::Equal {
-            a.stock_code.cmp(&b.stock_code)
-        } else {
-            primary
-        };
-        if sort.descending {
-            ord.reverse()
-        } else {
-            ord
-        }
-    });
-
-    rows
+    database.sorted_rows(codes, sort.field, sort.descending)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn rebuild_sorted_rows<'a>(
     database: &'a StockDatabase,
     codes: &[String],
@@ -400,7 +312,7 @@ pub fn run_results_table(
                 chart::render_chart_panel(
                     f,
                     chart_area,
-                    footer_height as u16,
+                    footer_height,
                     &chart_state,
                     selected_stock,
                 );
@@ -411,13 +323,11 @@ pub fn run_results_table(
             if let Event::Key(k) = event::read()? {
                 let total = rows_data.len();
                 match k.code {
-                    KeyCode::Enter => {
-                        if !chart_state.show {
-                            if let Some(stock) = rows_data.get(selected) {
-                                chart_state.show = true;
-                                chart_state.timeframe_index = 0;
-                                chart_state.prepare_history(region, &stock.stock_code);
-                            }
+                    KeyCode::Enter if !chart_state.show => {
+                        if let Some(stock) = rows_data.get(selected) {
+                            chart_state.show = true;
+                            chart_state.timeframe_index = 0;
+                            chart_state.prepare_history(region, &stock.stock_code);
                         }
                     }
                     KeyCode::Esc => {
@@ -465,23 +375,53 @@ pub fn run_results_table(
                             &mut chart_state,
                         );
                     }
-                    KeyCode::Right | KeyCode::Char('l') => {
+                    KeyCode::Right | KeyCode::Char('l') if chart_state.show => {
+                        chart_state.next_timeframe();
+                    }
+                    KeyCode::Left | KeyCode::Char('h') if chart_state.show => {
+                        chart_state.prev_timeframe();
+                    }
+                    KeyCode::Char('g') if chart_state.show => {
+                        chart_state.next_interval();
+                    }
+                    KeyCode::Char('G') if chart_state.show => {
+                        chart_state.prev_interval();
+                    }
+                    KeyCode::Char('a') if chart_state.show => {
+                        chart_state.cycle_aggregation();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') if total > 0 => {
+                        selected = (selected + 1) % total;
+                        if selected >= offset + capacity {
+                            offset = selected + 1 - capacity;
+                        } else if selected < offset {
+                            offset = selected;
+                        }
                         if chart_state.show {
-                            chart_state.next_timeframe();
+                            if let Some(stock) = rows_data.get(selected) {
+                                chart_state.prepare_history(region, &stock.stock_code);
+                            }
                         }
                     }
-                    KeyCode::Left | KeyCode::Char('h') => {
+                    KeyCode::Up | KeyCode::Char('k') if total > 0 => {
+                        selected = selected.checked_sub(1).unwrap_or(total - 1);
+                        if selected < offset {
+                            offset = selected;
+                        } else if selected >= offset + capacity {
+                            offset = selected + 1 - capacity;
+                        }
                         if chart_state.show {
-                            chart_state.prev_timeframe();
+                            if let Some(stock) = rows_data.get(selected) {
+                                chart_state.prepare_history(region, &stock.stock_code);
+                            }
                         }
                     }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        if total > 0 {
-                            selected = (selected + 1) % total;
+                    KeyCode::PageDown if total > 0 => {
+                        let new_selected = (selected + capacity).min(total.saturating_sub(1));
+                        if new_selected != selected {
+                            selected = new_selected;
                             if selected >= offset + capacity {
                                 offset = selected + 1 - capacity;
-                            } else if selected < offset {
-                                offset = selected;
                             }
                             if chart_state.show {
                                 if let Some(stock) = rows_data.get(selected) {
@@ -490,13 +430,12 @@ pub fn run_results_table(
                             }
                         }
                     }
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        if total > 0 {
-                            selected = selected.checked_sub(1).unwrap_or(total - 1);
+                    KeyCode::PageUp if total > 0 => {
+                        let new_selected = selected.saturating_sub(capacity);
+                        if new_selected != selected {
+                            selected = new_selected;
                             if selected < offset {
                                 offset = selected;
-                            } else if selected >= offset + capacity {
-                                offset = selected + 1 - capacity;
                             }
                             if chart_state.show {
                                 if let Some(stock) = rows_data.get(selected) {
@@ -505,57 +444,21 @@ pub fn run_results_table(
                             }
                         }
                     }
-                    KeyCode::PageDown => {
-                        if total > 0 {
-                            let new_selected = (selected + capacity).min(total.saturating_sub(1));
-                            if new_selected != selected {
-                                selected = new_selected;
-                                if selected >= offset + capacity {
-                                    offset = selected + 1 - capacity;
-                                }
-                                if chart_state.show {
-                                    if let Some(stock) = rows_data.get(selected) {
-                                        chart_state.prepare_history(region, &stock.stock_code);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    KeyCode::PageUp => {
-                        if total > 0 {
-                            let new_selected = selected.saturating_sub(capacity);
-                            if new_selected != selected {
-                                selected = new_selected;
-                                if selected < offset {
-                                    offset = selected;
-                                }
-                                if chart_state.show {
-                                    if let Some(stock) = rows_data.get(selected) {
-                                        chart_state.prepare_history(region, &stock.stock_code);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    KeyCode::Home => {
-                        if total > 0 {
-                            selected = 0;
-                            offset = 0;
-                            if chart_state.show {
-                                if let Some(stock) = rows_data.get(selected) {
-                                    chart_state.prepare_history(region, &stock.stock_code);
-                                }
+                    KeyCode::Home if total > 0 => {
+                        selected = 0;
+                        offset = 0;
+                        if chart_state.show {
+                            if let Some(stock) = rows_data.get(selected) {
+                                chart_state.prepare_history(region, &stock.stock_code);
                             }
                         }
                     }
-                    KeyCode::End => {
-                        if total > 0 {
-                            selected = total - 1;
-                            offset = selected.saturating_sub(capacity.saturating_sub(1));
-                            if chart_state.show {
-                                if let Some(stock) = rows_data.get(selected) {
-                                    chart_state.prepare_history(region, &stock.stock_code);
-                                }
+                    KeyCode::End if total > 0 => {
+                        selected = total - 1;
+                        offset = selected.saturating_sub(capacity.saturating_sub(1));
+                        if chart_state.show {
+                            if let Some(stock) = rows_data.get(selected) {
+                                chart_state.prepare_history(region, &stock.stock_code);
                             }
                         }
                     }
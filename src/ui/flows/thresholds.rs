@@ -16,9 +16,13 @@ use crate::{
     },
 };
 
+/// Callback invoked when the user saves the edited thresholds under a preset name.
+pub type SaveThresholdsCallback<'a> =
+    &'a mut dyn FnMut(&str, &HashMap<String, Threshold>) -> Result<String>;
+
 pub fn run_thresholds_editor(
     thresholds: &mut HashMap<String, Threshold>,
-    mut save_callback: Option<&mut dyn FnMut(&str, &HashMap<String, Threshold>) -> Result<String>>,
+    mut save_callback: Option<SaveThresholdsCallback<'_>>,
 ) -> Result<()> {
     // Guard terminal state while the modal editor is active.
     let mut guard = TerminalGuard::new()?;
@@ -46,7 +50,7 @@ pub fn run_thresholds_editor(
     }
 
     fn sort_metric_keys(
-        keys: &mut Vec<String>,
+        keys: &mut [String],
         thresholds: &std::collections::HashMap<String, Threshold>,
     ) {
         keys.sort_by(|a, b| {
@@ -411,10 +415,10 @@ pub fn run_thresholds_editor(
                                     up.push(c);
                                 }
                             }
-                            KeyCode::Char(' ') | KeyCode::Char('t') | KeyCode::Char('v') => {
-                                if fld == 2 {
-                                    is_valid = !is_valid;
-                                }
+                            KeyCode::Char(' ') | KeyCode::Char('t') | KeyCode::Char('v')
+                                if fld == 2 =>
+                            {
+                                is_valid = !is_valid;
                             }
                             KeyCode::Enter => {
                                 // Parse the buffered input, falling back to the original values when parsing fails.
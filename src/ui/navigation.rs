@@ -33,6 +33,7 @@ impl UiRoute {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MenuAction {
     Update,
+    Resume,
     Filter,
     Filters,
     Load,
@@ -11,6 +11,20 @@ pub struct FileEntry {
     pub size: u64,
 }
 
+/// Treat `value` as a path when it exists or contains a separator, otherwise as a name in `dir`.
+pub fn resolve_named_file(dir: &Path, value: &str, extension: &str) -> PathBuf {
+    let candidate = PathBuf::from(value);
+    if candidate.exists() || candidate.components().count() > 1 {
+        return candidate;
+    }
+
+    let mut path = dir.join(value);
+    if path.extension().is_none() {
+        path.set_extension(extension);
+    }
+    path
+}
+
 pub fn list_files_with_extension(dir: impl AsRef<Path>, extension: &str) -> Vec<FileEntry> {
     let mut entries = Vec::new();
     let dir_path = dir.as_ref();
@@ -46,7 +60,7 @@ pub fn list_files_with_extension(dir: impl AsRef<Path>, extension: &str) -> Vec<
         }
     }
 
-    entries.sort_by(|a, b| b.modified.cmp(&a.modified));
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.modified));
     entries
 }
 
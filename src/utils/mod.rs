@@ -1,7 +1,9 @@
 pub mod file;
+pub mod schedule;
 pub mod text;
 pub mod time;
 
-pub use file::{list_csv_files, list_json_files};
+pub use file::{list_csv_files, list_json_files, resolve_named_file};
+pub use schedule::{parse_interval, CronSchedule, Schedule};
 pub use text::sanitize_preset_name;
 pub use time::{current_human_timestamp, format_file_modified, snapshot_timestamp_slug};
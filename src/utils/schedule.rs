@@ -0,0 +1,319 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDateTime, TimeZone, Timelike,
+};
+
+use crate::error::{AppError, Result};
+
+/// When a recurring job should run next: a fixed interval or a five-field cron expression.
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    Interval(Duration),
+    Cron(CronSchedule),
+}
+
+impl Schedule {
+    /// First run time strictly after `now`, or `None` when the schedule never fires again.
+    pub fn next_after(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            Schedule::Interval(interval) => {
+                now.checked_add_signed(ChronoDuration::from_std(*interval).ok()?)
+            }
+            Schedule::Cron(cron) => cron.next_after(now),
+        }
+    }
+}
+
+/// Parse durations such as `90s`, `5m`, `1h`, or a bare number of seconds.
+pub fn parse_interval(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let (number, unit) = value
+        .find(|ch: char| !ch.is_ascii_digit())
+        .map(|idx| value.split_at(idx))
+        .unwrap_or((value, "s"));
+    let amount: u64 = number.parse().map_err(|_| invalid_interval(value))?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        _ => return Err(invalid_interval(value)),
+    };
+    match amount.checked_mul(multiplier) {
+        Some(seconds) if seconds > 0 => Ok(Duration::from_secs(seconds)),
+        _ => Err(invalid_interval(value)),
+    }
+}
+
+fn invalid_interval(value: &str) -> AppError {
+    AppError::message(format!(
+        "Invalid interval '{}'. Expected a positive duration such as 30s, 5m, or 1h.",
+        value
+    ))
+}
+
+/// Cron subset: `minute hour day-of-month month day-of-week`, each field accepting `*`,
+/// numbers, ranges `a-b`, lists `a,b`, and steps `*/n` or `a-b/n`. Evaluated in local time.
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    minutes: CronField,
+    hours: CronField,
+    days_of_month: CronField,
+    months: CronField,
+    days_of_week: CronField,
+}
+
+/// Upper bound on the search for the next matching minute (a little over four years).
+const MAX_CRON_SEARCH_MINUTES: i64 = 4 * 366 * 24 * 60;
+
+impl CronSchedule {
+    pub fn next_after(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let naive = now.naive_local();
+        let mut candidate = naive
+            .with_second(0)
+            .and_then(|minute| minute.with_nanosecond(0))
+            .unwrap_or(naive)
+            + ChronoDuration::minutes(1);
+
+        for _ in 0..MAX_CRON_SEARCH_MINUTES {
+            if self.matches(&candidate) {
+                if let Some(local) = Local.from_local_datetime(&candidate).earliest() {
+                    if local > now {
+                        return Some(local);
+                    }
+                }
+            }
+            candidate += ChronoDuration::minutes(1);
+        }
+
+        None
+    }
+
+    /// Whether some date can satisfy the day fields, so `0 0 30 2 *` is rejected up front
+    /// instead of never firing.
+    fn has_possible_day(&self) -> bool {
+        // A `*`-style day of month always includes the 1st, and a restricted weekday recurs in
+        // every month.
+        if self.days_of_month.any || !self.days_of_week.any {
+            return true;
+        }
+        (1..=12u32)
+            .filter(|month| self.months.contains(*month))
+            .any(|month| {
+                let longest = match month {
+                    2 => 29,
+                    4 | 6 | 9 | 11 => 30,
+                    _ => 31,
+                };
+                (1..=longest).any(|day| self.days_of_month.contains(day))
+            })
+    }
+
+    fn matches(&self, at: &NaiveDateTime) -> bool {
+        if !self.minutes.contains(at.minute())
+            || !self.hours.contains(at.hour())
+            || !self.months.contains(at.month())
+        {
+            return false;
+        }
+
+        let dom = self.days_of_month.contains(at.day());
+        let dow = self
+            .days_of_week
+            .contains(at.weekday().num_days_from_sunday());
+        // Standard cron: when both day fields are restricted, either one may match.
+        match (self.days_of_month.any, self.days_of_week.any) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let fields: Vec<&str> = value.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(AppError::message(format!(
+                "Invalid cron expression '{}'. Expected 5 fields: minute hour day month weekday.",
+                value
+            )));
+        }
+
+        let mut days_of_week = CronField::parse(fields[4], 0, 7, "weekday")?;
+        // Both 0 and 7 mean Sunday.
+        if days_of_week.contains(7) {
+            days_of_week.bits |= 1;
+        }
+
+        let schedule = Self {
+            minutes: CronField::parse(fields[0], 0, 59, "minute")?,
+            hours: CronField::parse(fields[1], 0, 23, "hour")?,
+            days_of_month: CronField::parse(fields[2], 1, 31, "day")?,
+            months: CronField::parse(fields[3], 1, 12, "month")?,
+            days_of_week,
+        };
+        if !schedule.has_possible_day() {
+            return Err(AppError::message(format!(
+                "Invalid cron expression '{}'. No selected month has the selected day.",
+                value
+            )));
+        }
+        Ok(schedule)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CronField {
+    bits: u64,
+    any: bool,
+}
+
+impl CronField {
+    fn parse(spec: &str, min: u32, max: u32, name: &str) -> Result<Self> {
+        let invalid = || {
+            AppError::message(format!(
+                "Invalid cron {} field '{}'. Expected values between {} and {}.",
+                name, spec, min, max
+            ))
+        };
+
+        let mut bits = 0u64;
+        for part in spec.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step: u32 = step.parse().map_err(|_| invalid())?;
+                    if step == 0 {
+                        return Err(invalid());
+                    }
+                    (range, step)
+                }
+                None => (part, 1),
+            };
+
+            let (start, end) = if range == "*" {
+                (min, max)
+            } else if let Some((start, end)) = range.split_once('-') {
+                (
+                    start.parse().map_err(|_| invalid())?,
+                    end.parse().map_err(|_| invalid())?,
+                )
+            } else {
+                let value: u32 = range.parse().map_err(|_| invalid())?;
+                (value, value)
+            };
+
+            if start < min || end > max || start > end {
+                return Err(invalid());
+            }
+
+            for value in (start..=end).step_by(step as usize) {
+                bits |= 1 << value;
+            }
+        }
+
+        Ok(Self {
+            bits,
+            // Like standard cron, `*/n` still counts as unrestricted for the day-field OR rule.
+            any: spec.starts_with('*'),
+        })
+    }
+
+    fn contains(&self, value: u32) -> bool {
+        self.bits & (1 << value) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn cron(expression: &str) -> CronSchedule {
+        expression.parse().unwrap()
+    }
+
+    #[test]
+    fn intervals_take_an_optional_unit() {
+        assert_eq!(parse_interval("45").unwrap(), Duration::from_secs(45));
+        assert_eq!(parse_interval(" 5m ").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_interval("2h").unwrap(), Duration::from_secs(7200));
+
+        assert!(parse_interval("0m").is_err());
+        assert!(parse_interval("5d").is_err());
+        assert!(parse_interval("1.5h").is_err());
+    }
+
+    #[test]
+    fn oversized_intervals_are_rejected_instead_of_overflowing() {
+        assert!(parse_interval(&format!("{}h", u64::MAX / 60)).is_err());
+        assert!(parse_interval(&format!("{}m", u64::MAX)).is_err());
+        assert!(parse_interval("99999999999999999999s").is_err());
+    }
+
+    #[test]
+    fn cron_fields_are_range_checked() {
+        assert!("*/5 9-15 * * 1-5".parse::<CronSchedule>().is_ok());
+        assert!("* * * *".parse::<CronSchedule>().is_err());
+        assert!("60 * * * *".parse::<CronSchedule>().is_err());
+        assert!("* * 0 * *".parse::<CronSchedule>().is_err());
+        assert!("*/0 * * * *".parse::<CronSchedule>().is_err());
+        assert!("30-10 * * * *".parse::<CronSchedule>().is_err());
+    }
+
+    #[test]
+    fn expressions_that_never_fire_are_rejected() {
+        let err = "0 0 30 2 *".parse::<CronSchedule>().unwrap_err();
+        assert!(err.to_string().contains("No selected month"));
+        assert!("0 0 31 4,6,9,11 *".parse::<CronSchedule>().is_err());
+
+        // Leap days and weekday alternatives can still fire.
+        assert!("0 0 29 2 *".parse::<CronSchedule>().is_ok());
+        assert!("0 0 30 2 1".parse::<CronSchedule>().is_ok());
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either_day() {
+        // The 1st of the month or any Monday; 2026-10-12 is a Monday.
+        let schedule = cron("0 9 1 * 1");
+        assert!(schedule.matches(&at("2026-10-01 09:00")));
+        assert!(schedule.matches(&at("2026-10-12 09:00")));
+        assert!(!schedule.matches(&at("2026-10-13 09:00")));
+    }
+
+    #[test]
+    fn stepped_star_day_field_still_requires_both_days() {
+        // Odd days of the month that are also Mondays.
+        let schedule = cron("0 9 */2 * 1");
+        assert!(!schedule.matches(&at("2026-10-12 09:00")));
+        assert!(!schedule.matches(&at("2026-10-13 09:00")));
+        assert!(schedule.matches(&at("2026-10-19 09:00")));
+    }
+
+    #[test]
+    fn sunday_is_zero_or_seven() {
+        assert!(cron("0 0 * * 0").matches(&at("2026-10-18 00:00")));
+        assert!(cron("0 0 * * 7").matches(&at("2026-10-18 00:00")));
+    }
+
+    #[test]
+    fn next_cron_run_is_strictly_later() {
+        let local = |value: &str| Local.from_local_datetime(&at(value)).unwrap();
+        let weekdays = Schedule::Cron(cron("30 9 * * 1-5"));
+
+        assert_eq!(
+            weekdays.next_after(local("2026-10-12 09:00")),
+            Some(local("2026-10-12 09:30"))
+        );
+        // Friday's run has just started, so the next one is on Monday.
+        assert_eq!(
+            weekdays.next_after(local("2026-10-16 09:30")),
+            Some(local("2026-10-19 09:30"))
+        );
+    }
+}